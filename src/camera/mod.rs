@@ -1,5 +1,5 @@
-use crate::gameplay::tractor::{TRACTOR_MAX_SPEED, Tractor};
-use avian3d::prelude::{LinearVelocity, RayCaster};
+use crate::gameplay::tractor::Tractor;
+use avian3d::prelude::{LinearVelocity, MaxLinearSpeed, RayCaster};
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::prelude::*;
 use bevy_editor_cam::controller::projections;
@@ -10,17 +10,21 @@ use bevy_rts_camera::RtsCamera;
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, spawn_camera);
     #[cfg(feature = "dev")]
+    if !app.is_plugin_added::<bevy_editor_cam::prelude::MinimalEditorCamPlugin>() {
+        app.add_plugins(bevy_editor_cam::prelude::DefaultEditorCamPlugins);
+    }
+    #[cfg(feature = "dev")]
     app.add_systems(Update, toggle_camera);
     app.add_systems(Update, move_rts_camera);
 }
 
 fn move_rts_camera(
     mut camera: Single<&mut RtsCamera>,
-    player: Single<(&Transform, &LinearVelocity), With<Tractor>>,
+    player: Single<(&Transform, &LinearVelocity, &MaxLinearSpeed), With<Tractor>>,
 ) {
     camera.target_focus.translation = player.0.translation;
     camera.snap = true;
-    camera.target_zoom = 1. - (player.1.length().abs() / TRACTOR_MAX_SPEED).clamp(0.01, 0.99);
+    camera.target_zoom = 1. - (player.1.length().abs() / player.2.0).clamp(0.01, 0.99);
 }
 
 pub fn spawn_camera(mut commands: Commands) {
@@ -15,6 +15,20 @@ use bevy::{
 #[derive(Event)]
 pub struct GameOver;
 
+/// Physics layers deciding what collides with what
+#[derive(PhysicsLayer, Default, Clone, Copy, Debug)]
+pub enum GameLayer {
+    /// the world: ground, trees, apples and rocks
+    #[default]
+    Default,
+    /// the tractor body, its wheels and attachments
+    Tractor,
+    Bullet,
+    Seed,
+    /// fruit carried in a trailer
+    Cargo,
+}
+
 #[derive(Component)]
 struct DespawnAfter(pub(super) Timer);
 
@@ -24,17 +38,31 @@ impl DespawnAfter {
     }
 }
 
+pub mod ability;
 pub mod apple;
+pub mod apple_bench;
+pub mod apple_kind;
+pub mod attachment;
+pub mod boss;
 pub mod bullet;
 pub mod controls;
 pub mod health;
 pub mod level;
+pub mod lives;
+pub mod loadout;
+pub mod parts;
 pub mod saw;
 pub mod score;
+pub mod steering;
+pub mod tint;
 pub mod tractor;
+pub mod trailer;
 pub mod tree;
+pub mod tree_species;
 pub mod turret;
 pub mod turret_aiming;
+pub mod vehicle;
+pub mod vehicle_kind;
 
 pub mod healthbars;
 /// contains the heads up display during game;
@@ -42,6 +70,10 @@ pub mod hud;
 mod seed;
 
 pub mod damage_indicator;
+pub mod fertility;
+pub mod flip;
+pub mod fuel;
+pub mod pickup;
 
 #[cfg(feature = "dev_native")]
 use bevy_simple_subsecond_system::hot;
@@ -128,13 +160,30 @@ pub(super) fn plugin(app: &mut App) {
     app.add_plugins(level::plugin);
     app.add_plugins(hud::hud_plugin);
     app.add_plugins(tractor::tractor_plugin);
+    app.add_plugins(vehicle::plugin);
+    app.add_plugins(vehicle_kind::plugin);
+    app.add_plugins(ability::plugin);
+    app.add_plugins(flip::plugin);
+    app.add_plugins(lives::plugin);
+    app.add_plugins(pickup::plugin);
+    app.add_plugins(fuel::plugin);
+    app.add_plugins(parts::plugin);
+    app.add_plugins(loadout::plugin);
+    app.add_plugins(attachment::plugin);
+    app.add_plugins(trailer::plugin);
     app.add_plugins(bullet::bullet_plugin);
     app.add_plugins(seed::plugin);
     app.add_plugins(turret_aiming::plugin);
     app.add_plugins(turret::turret_plugin);
     app.add_plugins(apple::plugin);
+    app.add_plugins(apple_kind::plugin);
+    app.add_plugins(steering::plugin);
     app.add_plugins(health::plugin);
+    app.add_plugins(tint::plugin);
     app.add_plugins(tree::plugin);
+    app.add_plugins(tree_species::plugin);
+    app.add_plugins(fertility::plugin);
+    app.add_plugins(boss::plugin);
     app.add_plugins(score::plugin);
     app.add_plugins(damage_indicator::plugin);
     app.add_plugins(saw::plugin);
@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+
+use crate::PausableSystems;
+use crate::gameplay::apple::Apple;
+use crate::gameplay::health::{DamageEvent, DamageKind, ExplosionEvent};
+use crate::screens::Screen;
+
+use super::*;
+
+/// Speed added forward when boosting
+const BOOST_SPEED: f32 = 14.0;
+const BOOST_SECS: f32 = 0.6;
+/// Damage to everything the vehicle touches while boosting
+const BOOST_RAM_DAMAGE: u32 = 3;
+const SHOCKWAVE_RADIUS: f32 = 10.0;
+const SHOCKWAVE_DAMAGE: u32 = 2;
+const SHOCKWAVE_PUSH: f32 = 12.0;
+const HORN_RADIUS: f32 = 16.0;
+const HORN_PUSH: f32 = 10.0;
+const SHIELD_SECS: f32 = 4.0;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Ability>();
+
+    app.add_observer(boost)
+        .add_observer(shockwave)
+        .add_observer(horn)
+        .add_observer(shield);
+
+    app.add_systems(
+        Update,
+        (tick_cooldowns, end_boost, end_shield, ram_while_boosting)
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The active abilities, each one is handled by its own observer on [`AbilityUsed`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum Ability {
+    /// shoots forward and rams through apples
+    Boost,
+    /// damages and pushes back everything around
+    Shockwave,
+    /// scares the apples away
+    Horn,
+    /// nothing hurts for a while
+    Shield,
+}
+
+impl Ability {
+    pub fn name(self) -> &'static str {
+        match self {
+            Ability::Boost => "Boost",
+            Ability::Shockwave => "Shockwave",
+            Ability::Horn => "Horn",
+            Ability::Shield => "Shield",
+        }
+    }
+
+    pub fn cooldown(self) -> Duration {
+        match self {
+            Ability::Boost => Duration::from_secs(5),
+            Ability::Shockwave => Duration::from_secs(12),
+            Ability::Horn => Duration::from_secs(8),
+            Ability::Shield => Duration::from_secs(15),
+        }
+    }
+}
+
+/// The ability a vehicle can use and how long until it can use it again
+#[derive(Component, Debug, Clone)]
+pub struct AbilitySlot {
+    pub ability: Ability,
+    pub cooldown: Timer,
+}
+
+impl AbilitySlot {
+    /// starts out ready to use
+    pub fn new(ability: Ability) -> Self {
+        let mut cooldown = Timer::new(ability.cooldown(), TimerMode::Once);
+        cooldown.tick(ability.cooldown());
+        Self { ability, cooldown }
+    }
+
+    /// Starts the cooldown and tells the ability to fire, does nothing while cooling down
+    pub fn try_use(&mut self, commands: &mut Commands, vehicle: Entity) -> bool {
+        if !self.cooldown.finished() {
+            return false;
+        }
+
+        self.cooldown.reset();
+        commands.trigger_targets(AbilityUsed(self.ability), vehicle);
+        true
+    }
+
+    /// 0.0 right after use, 1.0 when ready
+    pub fn readiness(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+}
+
+/// Triggered on a vehicle when it uses its ability
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AbilityUsed(pub Ability);
+
+/// The vehicle is boosting and rams everything it touches
+#[derive(Component, Debug)]
+pub struct Boosting {
+    timer: Timer,
+    /// the top speed to go back to after the boost
+    max_speed: f32,
+    /// what was already rammed, so it's hit once per boost
+    hit: HashSet<Entity>,
+}
+
+/// Nothing can hurt the vehicle
+#[derive(Component, Debug)]
+pub struct Shielded(Timer);
+
+impl Shielded {
+    pub fn for_secs(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+fn tick_cooldowns(time: Res<Time>, mut slots: Query<&mut AbilitySlot>) {
+    for mut slot in slots.iter_mut() {
+        slot.cooldown.tick(time.delta());
+    }
+}
+
+fn boost(
+    trigger: Trigger<AbilityUsed>,
+    mut commands: Commands,
+    mut vehicles: Query<(&Transform, &mut LinearVelocity, &mut MaxLinearSpeed)>,
+) {
+    if trigger.0 != Ability::Boost {
+        return;
+    }
+    let Ok((transform, mut velocity, mut max_speed)) = vehicles.get_mut(trigger.target()) else {
+        return;
+    };
+
+    velocity.0 += transform.forward() * BOOST_SPEED;
+    commands.entity(trigger.target()).insert(Boosting {
+        timer: Timer::from_seconds(BOOST_SECS, TimerMode::Once),
+        max_speed: max_speed.0,
+        hit: HashSet::new(),
+    });
+    max_speed.0 += BOOST_SPEED;
+}
+
+fn end_boost(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut vehicles: Query<(Entity, &mut Boosting, &mut MaxLinearSpeed)>,
+) {
+    for (entity, mut boosting, mut max_speed) in vehicles.iter_mut() {
+        if boosting.timer.tick(time.delta()).finished() {
+            max_speed.0 = boosting.max_speed;
+            commands.entity(entity).remove::<Boosting>();
+        }
+    }
+}
+
+fn ram_while_boosting(
+    mut vehicles: Query<(Entity, &mut Boosting, &CollidingEntities)>,
+    mut event_writer: EventWriter<DamageEvent>,
+) {
+    for (vehicle, mut boosting, colliding) in vehicles.iter_mut() {
+        for other in colliding.iter() {
+            if !boosting.hit.insert(*other) {
+                continue;
+            }
+
+            event_writer.write(DamageEvent {
+                value: BOOST_RAM_DAMAGE,
+                entity: *other,
+                source: Some(vehicle),
+                kind: DamageKind::Impact,
+                crit: false,
+            });
+        }
+    }
+}
+
+fn shockwave(
+    trigger: Trigger<AbilityUsed>,
+    vehicles: Query<&Transform>,
+    mut apples: Query<(&Transform, &mut LinearVelocity), With<Apple>>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
+) {
+    if trigger.0 != Ability::Shockwave {
+        return;
+    }
+    let Ok(transform) = vehicles.get(trigger.target()) else {
+        return;
+    };
+
+    explosion_writer.write(ExplosionEvent {
+        position: transform.translation,
+        radius: SHOCKWAVE_RADIUS,
+        damage: SHOCKWAVE_DAMAGE,
+        source: Some(trigger.target()),
+    });
+    push_apples(
+        transform.translation,
+        SHOCKWAVE_RADIUS,
+        SHOCKWAVE_PUSH,
+        &mut apples,
+    );
+}
+
+fn horn(
+    trigger: Trigger<AbilityUsed>,
+    vehicles: Query<&Transform>,
+    mut apples: Query<(&Transform, &mut LinearVelocity), With<Apple>>,
+) {
+    if trigger.0 != Ability::Horn {
+        return;
+    }
+    let Ok(transform) = vehicles.get(trigger.target()) else {
+        return;
+    };
+
+    push_apples(transform.translation, HORN_RADIUS, HORN_PUSH, &mut apples);
+}
+
+/// Throws the apples around a point away from it, the closer the harder
+fn push_apples(
+    center: Vec3,
+    radius: f32,
+    push: f32,
+    apples: &mut Query<(&Transform, &mut LinearVelocity), With<Apple>>,
+) {
+    for (transform, mut velocity) in apples.iter_mut() {
+        let away = transform.translation - center;
+        let distance = away.length();
+        if distance > radius {
+            continue;
+        }
+
+        let strength = push * (1.0 - distance / radius);
+        velocity.0 += (away.normalize_or_zero() + Vec3::Y * 0.5) * strength;
+    }
+}
+
+fn shield(trigger: Trigger<AbilityUsed>, mut commands: Commands) {
+    if trigger.0 != Ability::Shield {
+        return;
+    }
+
+    commands
+        .entity(trigger.target())
+        .insert(Shielded::for_secs(SHIELD_SECS));
+}
+
+fn end_shield(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut vehicles: Query<(Entity, &mut Shielded)>,
+) {
+    for (entity, mut shielded) in vehicles.iter_mut() {
+        if shielded.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Shielded>();
+        }
+    }
+}
@@ -2,13 +2,17 @@ use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
 use crate::audio::sound_effect;
 use crate::gameplay::DespawnAfter;
+use crate::gameplay::apple_kind::AppleKind;
 use crate::gameplay::health::{Death, Health};
-use crate::gameplay::healthbars::healthbar;
-use crate::gameplay::level::TERRAIN_HEIGHT;
+use crate::gameplay::level::{TERRAIN_HEIGHT, terrain_height};
 use crate::gameplay::saw::Sawable;
 use crate::gameplay::seed::SeedSpawnEvent;
+use crate::gameplay::steering::Steering;
+use crate::gameplay::tint::tinted;
 use crate::{ReplaceOnHotreload, gameplay::tractor::Tractor, screens::*};
 use avian3d::prelude::*;
+use bevy::color::palettes::css::BLACK;
+use bevy::gltf::{GltfMesh, GltfNode};
 use bevy::prelude::*;
 
 const APPLE_MASS: f32 = 1.0;
@@ -16,18 +20,65 @@ pub const APPLE_RADIUS: f32 = 1.2;
 const APPLE_INITIAL_VELOCITY: f32 = 10.0;
 const APPLE_INITIAL_ROTATION: f32 = 5.0;
 const APPLE_SEED_PROBABILITY: f32 = 0.35;
-const MAXIMUM_APPLES: usize = 300;
-use bevy_ui_anchor::AnchoredUiNodes;
+pub const MAXIMUM_APPLES: usize = 4000;
+/// How far apart the apples a splitter bursts into are spawned
+const SPLIT_SPREAD: f32 = 1.5;
+const APPLE_MODEL: &str = "models/apple/apple.gltf";
+/// The model has a whole, an eaten and an almost finished apple, each made of three nodes
+const APPLE_STAGES: usize = 3;
+const NODES_PER_STAGE: usize = 3;
+/// Apples further than this from the tractor stop colliding and slide over the ground
+const SIMPLE_MOTION_DISTANCE: f32 = 70.0;
+/// How much closer a far away apple has to come to collide again, so it doesn't flicker
+const SIMPLE_MOTION_HYSTERESIS: f32 = 10.0;
+/// Speed of a far away apple per unit of steering force
+const SIMPLE_SPEED_PER_FORCE: f32 = 0.5;
+/// How fast far away apples settle onto the ground
+const SIMPLE_SETTLE_SEC: f32 = 0.25;
+/// Where pooled apples wait to be spawned again
+const POOL_PARK: Vec3 = Vec3::new(0.0, -1000.0, 0.0);
+const HEALTHBAR_WIDTH: f32 = 1.6;
+/// Gap between the top of the apple and its health bar
+const HEALTHBAR_OFFSET: f32 = 0.6;
 
 #[derive(Component)]
 pub struct Apple {
     pub radius: f32,
 }
 
+/// A dead apple waiting to be spawned again, hidden and without physics
+#[derive(Component)]
+pub struct PooledApple;
+
+/// A far away apple that is moved along the ground without collisions
+#[derive(Component)]
+pub struct SimplifiedMotion;
+
+/// Which stage and kind the meshes of an apple currently show
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+struct AppleLook {
+    stage: usize,
+    kind: AppleKind,
+}
+
+impl AppleLook {
+    fn new(health: &Health, kind: AppleKind) -> Self {
+        let stage = match health.percentage() {
+            90.. => 0,
+            40.. => 1,
+            _ => 2,
+        };
+        Self { stage, kind }
+    }
+}
+
 #[derive(Event)]
 pub struct AppleSpawnEvent {
     pub at: Vec3,
     pub apple_strength: AppleStrength,
+    pub kind: AppleKind,
+    /// thrown at the tractor when not set
+    pub velocity: Option<Vec3>,
 }
 
 #[derive(Component, Clone, Debug)]
@@ -50,9 +101,8 @@ impl AppleStrength {
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct AppleAssets {
-    pub apple: Handle<Scene>,
-    eaten_apple: Handle<Scene>,
-    eaten_apple_2: Handle<Scene>,
+    /// the nodes of every stage, one stage after the other
+    nodes: Vec<Handle<GltfNode>>,
     death_sound: Handle<AudioSource>,
 }
 
@@ -60,28 +110,148 @@ impl FromWorld for AppleAssets {
     fn from_world(world: &mut World) -> Self {
         let assets: &AssetServer = world.resource::<AssetServer>();
         Self {
-            apple: assets.load(GltfAssetLabel::Scene(0).from_asset("models/apple/apple.gltf")),
-            eaten_apple: assets
-                .load(GltfAssetLabel::Scene(1).from_asset("models/apple/apple.gltf")),
-            eaten_apple_2: assets
-                .load(GltfAssetLabel::Scene(2).from_asset("models/apple/apple.gltf")),
+            nodes: (0..APPLE_STAGES * NODES_PER_STAGE)
+                .map(|node| assets.load(GltfAssetLabel::Node(node).from_asset(APPLE_MODEL)))
+                .collect(),
             death_sound: assets.load::<AudioSource>("audio/sound_effects/apple-death.wav"),
         }
     }
 }
 
+/// Meshes and materials shared by all apples, instead of a scene per apple
+#[derive(Resource)]
+pub struct AppleMeshes {
+    stages: Vec<Vec<ApplePartMesh>>,
+}
+
+struct ApplePartMesh {
+    mesh: Handle<Mesh>,
+    /// one per apple kind, in the order of `AppleKind::ALL`
+    materials: Vec<Handle<StandardMaterial>>,
+    transform: Transform,
+}
+
+/// Pulls the meshes out of the apple model once it is loaded, tinting a material per kind
+fn prepare_apple_meshes(
+    mut commands: Commands,
+    assets: Res<AppleAssets>,
+    nodes: Res<Assets<GltfNode>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut stages = Vec::with_capacity(APPLE_STAGES);
+    for stage_nodes in assets.nodes.chunks(NODES_PER_STAGE) {
+        let mut parts = Vec::new();
+        for node in stage_nodes {
+            // not loaded yet, try again next frame
+            let Some(node) = nodes.get(node) else {
+                return;
+            };
+            let Some(mesh) = node.mesh.as_ref() else {
+                continue;
+            };
+            let Some(mesh) = gltf_meshes.get(mesh) else {
+                return;
+            };
+            for primitive in mesh.primitives.iter() {
+                let material = primitive.material.clone().unwrap_or_default();
+                if !materials.contains(&material) {
+                    return;
+                }
+                parts.push((primitive.mesh.clone(), material, node.transform));
+            }
+        }
+        stages.push(parts);
+    }
+
+    let stages = stages
+        .into_iter()
+        .map(|parts| {
+            parts
+                .into_iter()
+                .map(|(mesh, material, transform)| {
+                    let base = materials.get(&material).cloned().unwrap_or_default();
+                    let materials = AppleKind::ALL
+                        .iter()
+                        .map(|kind| match kind.tint() {
+                            Some(tint) => materials.add(tinted(&base, tint)),
+                            None => material.clone(),
+                        })
+                        .collect();
+                    ApplePartMesh {
+                        mesh,
+                        materials,
+                        transform,
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    commands.insert_resource(AppleMeshes { stages });
+}
+
+/// Swaps the mesh children of the apple over to the look
+fn show_look(apple: &mut EntityCommands, meshes: &AppleMeshes, look: AppleLook) {
+    apple
+        .despawn_related::<Children>()
+        .insert(look)
+        .with_children(|apple| {
+            for part in meshes.stages[look.stage].iter() {
+                apple.spawn((
+                    Mesh3d(part.mesh.clone()),
+                    MeshMaterial3d(part.materials[look.kind as usize].clone()),
+                    part.transform,
+                ));
+            }
+        });
+}
+
+/// Puts the apple back into the pool instead of despawning it
+pub fn release_apple(commands: &mut Commands, apple: Entity) {
+    let Ok(mut ec) = commands.get_entity(apple) else {
+        return;
+    };
+
+    ec.remove::<(
+        Apple,
+        AppleKind,
+        AppleStrength,
+        Steering,
+        Health,
+        Sawable,
+        SimplifiedMotion,
+    )>()
+    .insert((
+        PooledApple,
+        RigidBody::Dynamic,
+        RigidBodyDisabled,
+        ColliderDisabled,
+        Visibility::Hidden,
+        Transform::from_translation(POOL_PARK),
+        LinearVelocity::ZERO,
+        AngularVelocity::ZERO,
+        ExternalForce::default(),
+    ));
+    AppleKind::remove_components(&mut ec);
+}
+
 fn spawn_apple_event_handler(
     mut events: EventReader<AppleSpawnEvent>,
     mut commands: Commands,
-    assets: Res<AppleAssets>,
+    meshes: Res<AppleMeshes>,
     tractor: Single<&Transform, With<Tractor>>,
-    apples: Query<&Apple>,
+    apples: Query<(), With<Apple>>,
+    pool: Query<(Entity, &AppleLook), With<PooledApple>>,
 ) {
+    let mut num_apples = apples.iter().len();
+    let mut pool = pool.iter();
+
     for event in events.read() {
-        let num_apples = apples.iter().len();
         if num_apples >= MAXIMUM_APPLES {
             continue;
         }
+        num_apples += 1;
 
         let position = event.at;
         let towards_player =
@@ -92,59 +262,110 @@ fn spawn_apple_event_handler(
         let apple_rotation =
             rot.mul_vec3((tractor.translation - position).normalize()) * APPLE_INITIAL_ROTATION;
 
+        let apple_strength = event.kind.strength(event.apple_strength.clone());
+
         let new_apple_radius =
-            APPLE_RADIUS + 0.1 * APPLE_RADIUS * (event.apple_strength.health - 1) as f32;
+            APPLE_RADIUS + 0.1 * APPLE_RADIUS * apple_strength.health.saturating_sub(1) as f32;
 
         let scale = new_apple_radius / APPLE_RADIUS;
 
-        commands
-            .spawn((
-                Apple {
-                    radius: new_apple_radius,
-                },
-                Sawable::default(),
-                Name::new("Apple"),
-                Health::new(event.apple_strength.health),
-                event.apple_strength.clone(),
-                Mass(APPLE_MASS),
-                ReplaceOnHotreload,
-                AnchoredUiNodes::spawn_one(healthbar(100.)),
-                RigidBody::Dynamic,
-                Collider::sphere(APPLE_RADIUS),
-                Transform::from_translation(position).with_scale(Vec3::splat(scale)),
-                LinearVelocity(towards_player * APPLE_INITIAL_VELOCITY),
-                AngularVelocity(apple_rotation),
-                SceneRoot(assets.apple.clone()),
-            ))
-            .observe(
-                |trigger: Trigger<Death>,
-                 mut commands: Commands,
-                 assets: Res<AppleAssets>,
-                 mut eventwriter: EventWriter<SeedSpawnEvent>,
-                 query: Query<(Entity, &Transform, &LinearVelocity), With<Apple>>| {
-                    if let Ok((_apple_e, apple_t, velocity)) = query.get(trigger.target()) {
-                        if rand::random::<f32>() <= APPLE_SEED_PROBABILITY {
-                            eventwriter.write(SeedSpawnEvent {
-                                position: apple_t.translation,
-                                velocity: **velocity,
-                            });
-                        }
-
-                        commands.spawn((
-                            apple_death_particles(),
-                            Transform::from_translation(apple_t.translation),
-                        ));
-                    }
-                    commands.spawn(sound_effect(assets.death_sound.clone()));
+        let look = AppleLook {
+            stage: 0,
+            kind: event.kind,
+        };
+        let mut apple = match pool.next() {
+            Some((entity, old_look)) => {
+                let mut apple = commands.entity(entity);
+                apple
+                    .remove::<(
+                        PooledApple,
+                        SimplifiedMotion,
+                        RigidBodyDisabled,
+                        ColliderDisabled,
+                    )>()
+                    .insert(Visibility::Inherited);
+                if *old_look != look {
+                    show_look(&mut apple, &meshes, look);
+                }
+                apple
+            }
+            None => {
+                let mut apple = commands.spawn((
+                    Name::new("Apple"),
+                    Mass(APPLE_MASS),
+                    ReplaceOnHotreload,
+                    Collider::sphere(APPLE_RADIUS),
+                    Visibility::Inherited,
+                ));
+                show_look(&mut apple, &meshes, look);
+                apple
+            }
+        };
 
-                    if let Ok(mut ec) = commands.get_entity(trigger.target()) {
-                        ec.despawn();
-                    }
-                },
-            );
+        apple.insert((
+            Apple {
+                radius: new_apple_radius,
+            },
+            Sawable::default(),
+            event.kind,
+            Steering::for_kind(event.kind),
+            Health::new(apple_strength.health),
+            apple_strength,
+            RigidBody::Dynamic,
+            Transform::from_translation(position).with_scale(Vec3::splat(scale)),
+            LinearVelocity(
+                event
+                    .velocity
+                    .unwrap_or(towards_player * APPLE_INITIAL_VELOCITY),
+            ),
+            AngularVelocity(apple_rotation),
+        ));
+        event.kind.insert_components(&mut apple);
     }
 }
 
+fn apple_death(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    assets: Res<AppleAssets>,
+    mut eventwriter: EventWriter<SeedSpawnEvent>,
+    mut apple_writer: EventWriter<AppleSpawnEvent>,
+    query: Query<(&Transform, &LinearVelocity, &AppleKind, &AppleStrength), With<Apple>>,
+) {
+    let Ok((apple_t, velocity, kind, strength)) = query.get(trigger.target()) else {
+        return;
+    };
+
+    if rand::random::<f32>() <= APPLE_SEED_PROBABILITY {
+        eventwriter.write(SeedSpawnEvent {
+            position: apple_t.translation,
+            velocity: **velocity,
+        });
+    }
+
+    for (kind, apple_strength) in kind.split(strength) {
+        let offset = Vec3::new(
+            rand::random::<f32>() - 0.5,
+            0.5,
+            rand::random::<f32>() - 0.5,
+        ) * SPLIT_SPREAD;
+        apple_writer.write(AppleSpawnEvent {
+            at: apple_t.translation + offset,
+            apple_strength,
+            kind,
+            velocity: None,
+        });
+    }
+
+    commands.spawn((
+        apple_death_particles(),
+        Transform::from_translation(apple_t.translation),
+    ));
+    commands.spawn(sound_effect(assets.death_sound.clone()));
+
+    release_apple(&mut commands, trigger.target());
+}
+
 pub fn apple_death_particles() -> impl Bundle {
     use bevy::color::palettes::css::YELLOW;
     use bevy_firework::{
@@ -185,45 +406,101 @@ pub fn apple_death_particles() -> impl Bundle {
     )
 }
 
-fn apply_apple_force(
-    mut query: Query<(&mut ExternalForce, &Transform, &AppleStrength), With<Apple>>,
-    tractor: Single<&Transform, With<Tractor>>,
-) {
-    for (mut apple_force, apple_transform, apple_strength) in query.iter_mut() {
-        let force = (tractor.translation - apple_transform.translation).normalize()
-            * (apple_strength.speed as f32 * 1.3 + 5.);
-
-        apple_force.set_force(force);
-    }
-}
-
 fn despawn_apples_below_map(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<Apple>>,
 ) {
     for (entity, transform) in query.iter() {
         if transform.translation.y < -1. * TERRAIN_HEIGHT {
-            commands.entity(entity).despawn();
+            release_apple(&mut commands, entity);
         }
     }
 }
 
-fn update_apple_mesh(
+/// Far away apples turn kinematic and lose their collider, close ones get full physics back
+fn simplify_distant_apples(
     mut commands: Commands,
-    query: Query<(Entity, &Health), (With<Apple>, Changed<Health>)>,
-    assets: Res<AppleAssets>,
+    apples: Query<(Entity, &Transform, &Apple, Has<SimplifiedMotion>)>,
+    tractor: Single<&Transform, (With<Tractor>, Without<Apple>)>,
 ) {
-    for (apple, health) in query {
-        // commands.entity(apple).remove::<SceneRoot>();
-        if let Ok(mut entity_commands) = commands.get_entity(apple) {
-            let health_percentage = health.percentage();
-            if health_percentage >= 90 {
-                entity_commands.try_insert(SceneRoot(assets.apple.clone()));
-            } else if health_percentage >= 40 {
-                entity_commands.try_insert(SceneRoot(assets.eaten_apple.clone()));
-            } else if health.current > 0 {
-                entity_commands.try_insert(SceneRoot(assets.eaten_apple_2.clone()));
+    for (entity, transform, apple, simplified) in apples.iter() {
+        let position = transform.translation;
+        let distance = position.xz().distance(tractor.translation.xz());
+
+        if !simplified && distance > SIMPLE_MOTION_DISTANCE {
+            // still flying, let the physics land it first
+            if position.y - terrain_height(position.x, position.z) > apple.radius * 2.0 {
+                continue;
             }
+            commands.entity(entity).insert((
+                SimplifiedMotion,
+                RigidBody::Kinematic,
+                ColliderDisabled,
+            ));
+        } else if simplified && distance < SIMPLE_MOTION_DISTANCE - SIMPLE_MOTION_HYSTERESIS {
+            commands
+                .entity(entity)
+                .remove::<(SimplifiedMotion, ColliderDisabled)>()
+                .insert(RigidBody::Dynamic);
+        }
+    }
+}
+
+/// Slides far away apples along their steering force, following the height of the terrain
+fn move_simplified_apples(
+    mut apples: Query<
+        (
+            &Transform,
+            &Apple,
+            &ExternalForce,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        With<SimplifiedMotion>,
+    >,
+) {
+    for (transform, apple, force, mut velocity, mut angular) in apples.iter_mut() {
+        let position = transform.translation;
+        let ground = terrain_height(position.x, position.z) + apple.radius;
+
+        velocity.0 = (force.force() * SIMPLE_SPEED_PER_FORCE)
+            .with_y((ground - position.y) / SIMPLE_SETTLE_SEC);
+        angular.0 = Vec3::ZERO;
+    }
+}
+
+/// Drawn with gizmos, a UI node per apple gets too slow with thousands of apples
+fn draw_apple_healthbars(
+    mut gizmos: Gizmos,
+    apples: Query<(&GlobalTransform, &Apple, &AppleKind, &Health)>,
+) {
+    for (transform, apple, kind, health) in apples.iter() {
+        // only damaged apples show their health
+        if health.current >= health.max {
+            continue;
+        }
+
+        let left = transform.translation()
+            + Vec3::new(-HEALTHBAR_WIDTH / 2., apple.radius + HEALTHBAR_OFFSET, 0.);
+        let filled = HEALTHBAR_WIDTH * health.current as f32 / health.max as f32;
+        gizmos.line(left, left + Vec3::X * HEALTHBAR_WIDTH, BLACK);
+        gizmos.line(left, left + Vec3::X * filled, kind.healthbar_color());
+    }
+}
+
+fn update_apple_mesh(
+    mut commands: Commands,
+    query: Query<(Entity, &Health, &AppleKind, &AppleLook), (With<Apple>, Changed<Health>)>,
+    meshes: Res<AppleMeshes>,
+) {
+    for (apple, health, kind, look) in query {
+        if health.current == 0 {
+            continue;
+        }
+
+        let new_look = AppleLook::new(health, *kind);
+        if new_look != *look {
+            show_look(&mut commands.entity(apple), &meshes, new_look);
         }
     }
 }
@@ -233,14 +510,26 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<AppleAssets>();
 
     app.add_event::<AppleSpawnEvent>();
+    app.add_observer(apple_death);
+    app.add_systems(
+        Update,
+        prepare_apple_meshes
+            .run_if(resource_exists::<AppleAssets>.and(not(resource_exists::<AppleMeshes>))),
+    );
     app.add_systems(
         Update,
         (
-            spawn_apple_event_handler.run_if(in_state(Screen::InGame)),
-            apply_apple_force.run_if(in_state(Screen::InGame)),
-            despawn_apples_below_map.run_if(in_state(Screen::InGame)),
-            update_apple_mesh.run_if(in_state(Screen::InGame)),
+            spawn_apple_event_handler.run_if(resource_exists::<AppleMeshes>),
+            despawn_apples_below_map,
+            update_apple_mesh.run_if(resource_exists::<AppleMeshes>),
+            simplify_distant_apples,
+            move_simplified_apples,
         )
+            .run_if(in_state(Screen::InGame))
             .in_set(PausableSystems),
     );
+    app.add_systems(
+        Update,
+        draw_apple_healthbars.run_if(in_state(Screen::InGame)),
+    );
 }
@@ -0,0 +1,208 @@
+//! Headless scene that spawns a lot of apples and measures the frame time,
+//! run it with `cargo run --release -- --bench-apples 3000`
+
+use std::time::Instant;
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+
+use crate::gameplay::apple::{
+    Apple, AppleMeshes, AppleSpawnEvent, AppleStrength, MAXIMUM_APPLES, PooledApple,
+    SimplifiedMotion,
+};
+use crate::gameplay::apple_kind::AppleSpawnWeights;
+use crate::gameplay::level::{
+    LEVEL_OFFSET, PLANE_X_SIZE, PLANE_Z_SIZE, terrain_height, terrain_mesh,
+};
+use crate::gameplay::tractor::Tractor;
+use crate::screens::Screen;
+use crate::{PausableSystems, Pause};
+
+use super::*;
+
+/// Frames to let the apples land and spread out before measuring
+const WARMUP_FRAMES: u32 = 120;
+const MEASURED_FRAMES: u32 = 600;
+/// Level of the trees the benchmark apples come from
+const APPLE_LEVEL: u32 = 3;
+/// Radius of the circle the tractor drives in, so apples switch between near and far
+const TRACTOR_CIRCLE_RADIUS: f32 = 60.0;
+const TRACTOR_SPEED: f32 = 12.0;
+
+/// Number of apples passed with `--bench-apples`
+pub fn apples_from_args() -> Option<usize> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--bench-apples");
+    args.nth(1).and_then(|apples| apples.parse().ok())
+}
+
+pub fn run(apples: usize) -> AppExit {
+    if apples > MAXIMUM_APPLES {
+        warn!("only {MAXIMUM_APPLES} apples can be alive at once, the rest will not spawn");
+    }
+
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                // no gpu needed, the meshes are only loaded, not drawn
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+        ScheduleRunnerPlugin::run_loop(Duration::ZERO),
+        PhysicsPlugins::default(),
+    ));
+
+    app.init_state::<Pause>();
+    app.insert_state(Screen::InGame);
+    app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+
+    app.add_plugins((
+        crate::asset_tracking::plugin,
+        apple::plugin,
+        apple_kind::plugin,
+        steering::plugin,
+    ));
+
+    app.insert_resource(Bench {
+        apples,
+        spawned: false,
+        frame: 0,
+        frame_times: Vec::with_capacity(MEASURED_FRAMES as usize),
+        last_frame: Instant::now(),
+    });
+    app.add_systems(Startup, spawn_bench_scene);
+    app.add_systems(
+        Update,
+        (
+            drive_tractor,
+            spawn_bench_apples.run_if(resource_exists::<AppleMeshes>),
+            measure_frames,
+        )
+            .chain(),
+    );
+
+    app.run()
+}
+
+#[derive(Resource)]
+struct Bench {
+    apples: usize,
+    spawned: bool,
+    frame: u32,
+    frame_times: Vec<f32>,
+    last_frame: Instant,
+}
+
+fn spawn_bench_scene(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Ground"),
+        RigidBody::Static,
+        Collider::trimesh_from_mesh(&terrain_mesh()).unwrap_or(Collider::half_space(Vec3::Y)),
+        Transform::from_xyz(0., LEVEL_OFFSET, 0.),
+    ));
+
+    commands.spawn((
+        Name::new("Tractor"),
+        Tractor,
+        RigidBody::Kinematic,
+        Collider::cuboid(3., 2., 4.),
+        Transform::from_xyz(
+            TRACTOR_CIRCLE_RADIUS,
+            terrain_height(TRACTOR_CIRCLE_RADIUS, 0.),
+            0.,
+        ),
+    ));
+}
+
+fn drive_tractor(
+    time: Res<Time>,
+    tractor: Single<(&Transform, &mut LinearVelocity), With<Tractor>>,
+) {
+    let (transform, mut velocity) = tractor.into_inner();
+    let position = transform.translation;
+    let angle = time.elapsed_secs() * TRACTOR_SPEED / TRACTOR_CIRCLE_RADIUS;
+    let target = Vec3::new(angle.cos(), 0., angle.sin()) * TRACTOR_CIRCLE_RADIUS;
+    let target = target.with_y(terrain_height(target.x, target.z) + 1.);
+
+    velocity.0 = (target - position).clamp_length_max(TRACTOR_SPEED * 2.);
+}
+
+/// Drops all apples at once all over the map once their meshes are ready
+fn spawn_bench_apples(mut bench: ResMut<Bench>, mut events: EventWriter<AppleSpawnEvent>) {
+    if bench.spawned {
+        return;
+    }
+
+    let weights = AppleSpawnWeights::for_level(APPLE_LEVEL);
+    for _ in 0..bench.apples {
+        let x = (rand::random::<f32>() - 0.5) * PLANE_X_SIZE * 0.6;
+        let z = (rand::random::<f32>() - 0.5) * PLANE_Z_SIZE * 0.6;
+        events.write(AppleSpawnEvent {
+            at: Vec3::new(x, terrain_height(x, z) + 3., z),
+            apple_strength: AppleStrength::from_tree_level(APPLE_LEVEL),
+            kind: weights.pick(),
+            velocity: Some(Vec3::ZERO),
+        });
+    }
+
+    bench.spawned = true;
+    bench.last_frame = Instant::now();
+    info!("spawned {} apples", bench.apples);
+}
+
+fn measure_frames(
+    mut bench: ResMut<Bench>,
+    apples: Query<Has<SimplifiedMotion>, With<Apple>>,
+    pooled: Query<(), With<PooledApple>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !bench.spawned {
+        return;
+    }
+
+    let now = Instant::now();
+    let frame_time = now.duration_since(bench.last_frame).as_secs_f32() * 1000.;
+    bench.last_frame = now;
+    bench.frame += 1;
+
+    if bench.frame <= WARMUP_FRAMES {
+        return;
+    }
+    bench.frame_times.push(frame_time);
+    if bench.frame < WARMUP_FRAMES + MEASURED_FRAMES {
+        return;
+    }
+
+    let mut times = std::mem::take(&mut bench.frame_times);
+    times.sort_by(f32::total_cmp);
+    let average = times.iter().sum::<f32>() / times.len() as f32;
+    let percentile = |p: f32| times[((times.len() - 1) as f32 * p) as usize];
+    let simplified = apples.iter().filter(|simplified| *simplified).count();
+
+    info!(
+        "{} apples ({} simplified, {} pooled) over {} frames: \
+         average {:.2}ms, median {:.2}ms, 99th percentile {:.2}ms, worst {:.2}ms",
+        apples.iter().len(),
+        simplified,
+        pooled.iter().len(),
+        times.len(),
+        average,
+        percentile(0.5),
+        percentile(0.99),
+        percentile(1.0),
+    );
+    exit.write(AppExit::Success);
+}
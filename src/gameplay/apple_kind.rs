@@ -0,0 +1,222 @@
+use crate::PausableSystems;
+use crate::gameplay::apple::{Apple, AppleStrength};
+use crate::gameplay::health::{DamageKind, ExplosionEvent, Resistances};
+use crate::screens::Screen;
+use bevy::color::palettes::tailwind::{
+    AMBER_300, GREEN_300, LIME_700, PURPLE_400, SKY_300, ZINC_400,
+};
+
+use super::*;
+
+/// Bonus points for a golden apple
+const GOLDEN_POINTS: u32 = 5;
+const ROTTEN_EXPLOSION_RADIUS: f32 = 6.0;
+const ROTTEN_EXPLOSION_DAMAGE: u32 = 3;
+/// How many small apples a splitter bursts into
+const SPLITTER_CHILDREN: usize = 3;
+const BOUNCER_RESTITUTION: f32 = 0.9;
+const BOUNCER_HOP_INTERVAL_SEC: f32 = 1.2;
+const BOUNCER_HOP_VELOCITY: f32 = 9.0;
+const ARMORED_BULLET_MULTIPLIER: f32 = 0.25;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<AppleKind>();
+    app.register_type::<AppleSpawnWeights>();
+
+    app.add_systems(
+        Update,
+        hop_bouncers
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The different apples a tree can drop
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum AppleKind {
+    #[default]
+    Normal,
+    /// worth bonus points, runs away from the tractor
+    Golden,
+    /// explodes when it touches the tractor
+    Rotten,
+    /// splits into smaller apples when it dies
+    Splitter,
+    /// bouncy and keeps hopping around
+    Bouncer,
+    /// bullets barely hurt it
+    Armored,
+}
+
+impl AppleKind {
+    pub const ALL: [AppleKind; 6] = [
+        AppleKind::Normal,
+        AppleKind::Golden,
+        AppleKind::Rotten,
+        AppleKind::Splitter,
+        AppleKind::Bouncer,
+        AppleKind::Armored,
+    ];
+
+    /// multiplied with the base color of the apple materials
+    pub fn tint(self) -> Option<Color> {
+        match self {
+            AppleKind::Normal => None,
+            AppleKind::Golden => Some(AMBER_300.into()),
+            AppleKind::Rotten => Some(LIME_700.into()),
+            AppleKind::Splitter => Some(PURPLE_400.into()),
+            AppleKind::Bouncer => Some(SKY_300.into()),
+            AppleKind::Armored => Some(ZINC_400.into()),
+        }
+    }
+
+    pub fn healthbar_color(self) -> Color {
+        match self {
+            AppleKind::Normal => GREEN_300.into(),
+            AppleKind::Golden => GOLD.into(),
+            AppleKind::Rotten => OLIVE.into(),
+            AppleKind::Splitter => VIOLET.into(),
+            AppleKind::Bouncer => DEEP_SKY_BLUE.into(),
+            AppleKind::Armored => SILVER.into(),
+        }
+    }
+
+    pub fn points(self) -> u32 {
+        match self {
+            AppleKind::Golden => GOLDEN_POINTS,
+            _ => 1,
+        }
+    }
+
+    pub fn strength(self, base: AppleStrength) -> AppleStrength {
+        match self {
+            AppleKind::Golden => AppleStrength {
+                health: base.health.div_ceil(2),
+                damage: 0,
+                speed: base.speed + 2,
+            },
+            AppleKind::Armored => AppleStrength {
+                health: base.health * 2,
+                speed: base.speed.saturating_sub(1).max(1),
+                ..base
+            },
+            _ => base,
+        }
+    }
+
+    /// Components that only some kinds of apples have
+    pub fn insert_components(self, entity: &mut EntityCommands) {
+        match self {
+            AppleKind::Bouncer => {
+                entity.insert((
+                    Restitution::new(BOUNCER_RESTITUTION)
+                        .with_combine_rule(CoefficientCombine::Max),
+                    Hopper(Timer::from_seconds(
+                        BOUNCER_HOP_INTERVAL_SEC,
+                        TimerMode::Repeating,
+                    )),
+                ));
+            }
+            AppleKind::Armored => {
+                entity.insert(
+                    Resistances::default().with(DamageKind::Bullet, ARMORED_BULLET_MULTIPLIER),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the components of `insert_components` off again, for apples going back to the pool
+    pub fn remove_components(entity: &mut EntityCommands) {
+        entity.remove::<(Restitution, Hopper, Resistances)>();
+    }
+
+    /// Rotten apples blow up when they touch the tractor
+    pub fn contact_explosion(self, position: Vec3, apple: Entity) -> Option<ExplosionEvent> {
+        (self == AppleKind::Rotten).then_some(ExplosionEvent {
+            position,
+            radius: ROTTEN_EXPLOSION_RADIUS,
+            damage: ROTTEN_EXPLOSION_DAMAGE,
+            source: Some(apple),
+        })
+    }
+
+    /// Apples a splitter leaves behind when it dies
+    pub fn split(self, strength: &AppleStrength) -> Vec<(AppleKind, AppleStrength)> {
+        if self != AppleKind::Splitter {
+            return Vec::new();
+        }
+
+        let child = AppleStrength {
+            health: strength.health.div_ceil(2),
+            damage: strength.damage.div_ceil(2),
+            speed: strength.speed + 1,
+        };
+        vec![(AppleKind::Normal, child); SPLITTER_CHILDREN]
+    }
+}
+
+/// How likely each kind of apple is to drop from a tree
+#[derive(Component, Clone, Debug, Reflect)]
+pub struct AppleSpawnWeights(pub Vec<(AppleKind, f32)>);
+
+impl AppleSpawnWeights {
+    /// Young trees mostly drop normal apples, older trees get more variety
+    pub fn for_level(level: u32) -> Self {
+        let level = level as f32;
+        Self(vec![
+            (AppleKind::Normal, 10.0),
+            (AppleKind::Golden, 0.5 + 0.1 * level),
+            (AppleKind::Rotten, 0.5 * level),
+            (AppleKind::Splitter, 0.4 * level),
+            (AppleKind::Bouncer, 0.4 * level),
+            (AppleKind::Armored, (0.5 * (level - 2.0)).max(0.0)),
+        ])
+    }
+
+    /// Multiplies the weight of each listed kind
+    pub fn scaled(mut self, multipliers: &[(AppleKind, f32)]) -> Self {
+        for (kind, weight) in self.0.iter_mut() {
+            if let Some((_, multiplier)) = multipliers.iter().find(|(k, _)| k == kind) {
+                *weight *= multiplier;
+            }
+        }
+        self
+    }
+
+    pub fn pick(&self) -> AppleKind {
+        let total: f32 = self.0.iter().map(|(_, weight)| weight.max(0.0)).sum();
+        let mut roll = rand::random::<f32>() * total;
+
+        for (kind, weight) in self.0.iter() {
+            roll -= weight.max(0.0);
+            if roll <= 0.0 {
+                return *kind;
+            }
+        }
+
+        AppleKind::Normal
+    }
+}
+
+impl Default for AppleSpawnWeights {
+    fn default() -> Self {
+        Self::for_level(1)
+    }
+}
+
+/// Makes the apple jump every now and then
+#[derive(Component)]
+struct Hopper(Timer);
+
+fn hop_bouncers(
+    time: Res<Time>,
+    mut bouncers: Query<(&mut Hopper, &mut LinearVelocity), With<Apple>>,
+) {
+    for (mut hopper, mut velocity) in bouncers.iter_mut() {
+        hopper.0.tick(time.delta());
+        if hopper.0.just_finished() && velocity.y.abs() < 1.0 {
+            velocity.y = BOUNCER_HOP_VELOCITY;
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::gameplay::tractor::tractor_collision_layers;
+use crate::gameplay::vehicle_kind::VehicleSpec;
+use crate::screens::Screen;
+use crate::{PausableSystems, ReplaceOnHotreload};
+use bevy::color::palettes::tailwind::{RED_600, ZINC_500};
+
+use super::*;
+
+pub const PLOW_MASS: f32 = 2.0;
+/// How far the blade is turned, so apples slide off to the side
+const PLOW_ANGLE: f32 = 20.0;
+const PLOW_DEPTH: f32 = 0.3;
+const PLOW_HEIGHT: f32 = 0.5;
+const MAGNET_RADIUS: f32 = 15.0;
+/// Acceleration towards the magnet right next to it, fades out towards the edge
+const MAGNET_PULL: f32 = 40.0;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        pull_magnetic
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Pulls everything `Magnetic` around the entity towards it
+#[derive(Component, Debug, Clone)]
+pub struct Magnet {
+    pub radius: f32,
+    pub pull: f32,
+}
+
+impl Default for Magnet {
+    fn default() -> Self {
+        Self {
+            radius: MAGNET_RADIUS,
+            pull: MAGNET_PULL,
+        }
+    }
+}
+
+/// Gets pulled in by magnets
+#[derive(Component, Debug, Default)]
+pub struct Magnetic;
+
+#[derive(Component, Debug)]
+pub struct Plow;
+
+/// Hangs a magnet on top of the tractor
+pub fn magnet(
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    spec: &VehicleSpec,
+) -> impl Bundle {
+    (
+        Name::new("Magnet"),
+        Mesh3d(meshes.add(Torus::new(0.25, 0.45))),
+        MeshMaterial3d(materials.add(StandardMaterial::from_color(RED_600))),
+        Transform::from_xyz(0.0, spec.top() + 0.3, spec.size.z * 0.375)
+            .with_rotation(Quat::from_rotation_x(PI / 2.0)),
+    )
+}
+
+pub fn spawn_plow(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    spec: &VehicleSpec,
+    tractor_id: Entity,
+) {
+    let width = spec.size.x + spec.wheel_radius * 4.0 + 0.6;
+    let position = Vec3::new(
+        0.0,
+        -spec.top() + spec.wheel_radius / 2.0 + 0.1,
+        -(spec.size.z / 2.0 + 0.5),
+    );
+    let rotation = Quat::from_rotation_y(PLOW_ANGLE.to_radians());
+
+    let plow = commands
+        .spawn((
+            Name::new("Plow"),
+            Plow,
+            ReplaceOnHotreload,
+            RigidBody::Dynamic,
+            Mass(PLOW_MASS),
+            Transform::from_translation(position),
+            // the blade is turned on a child, the joint keeps the body straight
+            children![(
+                Collider::cuboid(width, PLOW_HEIGHT, PLOW_DEPTH),
+                tractor_collision_layers(),
+                Mesh3d(meshes.add(Cuboid::new(width, PLOW_HEIGHT, PLOW_DEPTH))),
+                MeshMaterial3d(materials.add(StandardMaterial::from_color(ZINC_500))),
+                Transform::from_rotation(rotation),
+            )],
+        ))
+        .id();
+
+    commands.spawn((
+        ReplaceOnHotreload,
+        FixedJoint::new(tractor_id, plow)
+            .with_local_anchor_1(position)
+            .with_local_anchor_2(Vec3::ZERO),
+    ));
+}
+
+fn pull_magnetic(
+    time: Res<Time>,
+    magnets: Query<(&GlobalTransform, &Magnet)>,
+    mut magnetic: Query<(&Transform, &mut LinearVelocity), With<Magnetic>>,
+) {
+    for (magnet_t, magnet) in magnets.iter() {
+        let center = magnet_t.translation();
+
+        for (transform, mut velocity) in magnetic.iter_mut() {
+            let to_magnet = center - transform.translation;
+            let distance = to_magnet.length();
+            if distance > magnet.radius || distance <= 0.0 {
+                continue;
+            }
+
+            let strength = magnet.pull * (1.0 - distance / magnet.radius);
+            velocity.0 += to_magnet / distance * strength * time.delta_secs();
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use crate::PausableSystems;
+use crate::gameplay::apple::{APPLE_RADIUS, AppleSpawnEvent, AppleStrength, apple_death_particles};
+use crate::gameplay::apple_kind::AppleSpawnWeights;
+use crate::gameplay::health::{Death, ExplosionEvent, Health};
+use crate::gameplay::score::{Currency, ScoreCounter};
+use crate::gameplay::tractor::Tractor;
+use crate::gameplay::tree::{TREE_STARTING_HEIGHT, Tree, TreeSpawnEvent, shake_tree};
+use crate::gameplay::tree_species::{TreeSpecies, VolleyPattern};
+use crate::screens::Screen;
+use bevy::color::palettes::tailwind::{AMBER_700, RED_500};
+use bevy_tweening::lens::TransformScaleLens;
+use bevy_tweening::{Animator, Tween};
+
+use super::*;
+
+/// A boss appears every time the score passes another step
+const BOSS_POINTS_STEP: usize = 150;
+/// and at the latest after this long without a boss
+const BOSS_INTERVAL_SEC: u64 = 240;
+pub const BOSS_LEVEL: u32 = 8;
+const BOSS_HEALTH_MULTIPLIER: u32 = 6;
+const BOSS_REWARD_POINTS: usize = 50;
+const BOSS_REWARD_UPGRADES: u32 = 5;
+
+const BARRAGE_APPLES: usize = 8;
+const BARRAGE_SPREAD: f32 = 4.0;
+const ROOT_SLAM_RADIUS: f32 = 7.0;
+const ROOT_SLAM_DAMAGE: u32 = 6;
+/// time between the warning and the slam
+const ROOT_SLAM_DELAY_MS: u64 = 1200;
+const SAPLINGS: usize = 3;
+const SAPLING_DISTANCE: (f32, f32) = (10.0, 25.0);
+
+const DEATH_BURSTS: u32 = 12;
+const DEATH_BURST_INTERVAL_MS: u64 = 150;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Boss>();
+    app.insert_resource(BossSchedule::default());
+
+    app.add_systems(OnEnter(Screen::InGame), |mut commands: Commands| {
+        commands.insert_resource(BossSchedule::default());
+    });
+
+    app.add_observer(setup_boss);
+    app.add_observer(boss_death);
+
+    app.add_systems(
+        Update,
+        (
+            schedule_bosses,
+            update_boss_phase,
+            boss_attacks,
+            root_slams,
+            death_sequences,
+            update_boss_healthbar,
+        )
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// When the next boss shows up
+#[derive(Resource, Debug)]
+struct BossSchedule {
+    next_points: usize,
+    timer: Timer,
+}
+
+impl Default for BossSchedule {
+    fn default() -> Self {
+        Self {
+            next_points: BOSS_POINTS_STEP,
+            timer: Timer::new(Duration::from_secs(BOSS_INTERVAL_SEC), TimerMode::Repeating),
+        }
+    }
+}
+
+/// A huge ancient tree, gets harder to deal with as it loses health
+#[derive(Component, Debug, Reflect)]
+pub struct Boss {
+    pub phase: BossPhase,
+    attack: Timer,
+    attacks: u32,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self {
+            phase: BossPhase::Barrage,
+            attack: Timer::new(BossPhase::Barrage.attack_interval(), TimerMode::Repeating),
+            attacks: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BossPhase {
+    /// throws volleys of apples
+    Barrage,
+    /// also slams its roots where the tractor is
+    RootSlam,
+    /// also grows saplings around itself
+    Saplings,
+}
+
+impl BossPhase {
+    fn from_health(health: &Health) -> Self {
+        match health.percentage() {
+            67.. => BossPhase::Barrage,
+            34..67 => BossPhase::RootSlam,
+            _ => BossPhase::Saplings,
+        }
+    }
+
+    fn attack_interval(self) -> Duration {
+        match self {
+            BossPhase::Barrage => Duration::from_secs(6),
+            BossPhase::RootSlam => Duration::from_secs(5),
+            BossPhase::Saplings => Duration::from_secs(4),
+        }
+    }
+
+    fn attack(self, count: u32) -> BossAttack {
+        let attacks: &[BossAttack] = match self {
+            BossPhase::Barrage => &[BossAttack::Barrage],
+            BossPhase::RootSlam => &[BossAttack::Barrage, BossAttack::RootSlam],
+            BossPhase::Saplings => &[
+                BossAttack::Barrage,
+                BossAttack::RootSlam,
+                BossAttack::Saplings,
+            ],
+        };
+        attacks[count as usize % attacks.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BossAttack {
+    Barrage,
+    RootSlam,
+    Saplings,
+}
+
+/// Warning on the ground before the roots come up
+#[derive(Component)]
+struct RootSlam {
+    timer: Timer,
+    boss: Entity,
+}
+
+#[derive(Component)]
+struct BossDeathSequence {
+    timer: Timer,
+    bursts: u32,
+}
+
+#[derive(Component)]
+struct BossHealthBar;
+#[derive(Component)]
+struct BossHealthBarFill;
+
+fn schedule_bosses(
+    mut schedule: ResMut<BossSchedule>,
+    time: Res<Time>,
+    score: Res<ScoreCounter>,
+    bosses: Query<(), With<Boss>>,
+    mut tree_writer: EventWriter<TreeSpawnEvent>,
+) {
+    if !bosses.is_empty() {
+        schedule.timer.reset();
+        return;
+    }
+
+    schedule.timer.tick(time.delta());
+    if score.points < schedule.next_points && !schedule.timer.just_finished() {
+        return;
+    }
+
+    while schedule.next_points <= score.points {
+        schedule.next_points += BOSS_POINTS_STEP;
+    }
+
+    let angle = rand::random::<f32>() * 2.0 * PI;
+    tree_writer.write(TreeSpawnEvent {
+        position: Vec3::new(angle.cos() * 60.0, 1000., angle.sin() * 60.0),
+        startlevel: BOSS_LEVEL,
+        boss: true,
+        species: Some(TreeSpecies::Oak),
+    });
+}
+
+fn setup_boss(
+    trigger: Trigger<OnAdd, Boss>,
+    mut commands: Commands,
+    mut bosses: Query<&mut Health, With<Boss>>,
+) {
+    if let Ok(mut health) = bosses.get_mut(trigger.target()) {
+        let max = health.max * BOSS_HEALTH_MULTIPLIER;
+        health.set_max_to(max);
+    }
+    commands
+        .entity(trigger.target())
+        .insert(Name::new("Ancient Tree"));
+}
+
+fn update_boss_phase(
+    mut commands: Commands,
+    mut bosses: Query<(Entity, &mut Boss, &Health), Changed<Health>>,
+) {
+    for (entity, mut boss, health) in bosses.iter_mut() {
+        let phase = BossPhase::from_health(health);
+        if phase == boss.phase {
+            continue;
+        }
+
+        boss.phase = phase;
+        boss.attack = Timer::new(phase.attack_interval(), TimerMode::Repeating);
+        // the new phase starts with its new attack right away
+        boss.attacks = match phase {
+            BossPhase::Barrage => 0,
+            BossPhase::RootSlam => 1,
+            BossPhase::Saplings => 2,
+        };
+        boss.attack.set_elapsed(phase.attack_interval());
+
+        commands
+            .entity(entity)
+            .try_insert(Animator::new(shake_tree()));
+    }
+}
+
+fn boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bosses: Query<(Entity, &mut Boss, &Tree, &AppleSpawnWeights, &Transform)>,
+    tractor: Single<&Transform, (With<Tractor>, Without<Boss>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut apple_writer: EventWriter<AppleSpawnEvent>,
+    mut tree_writer: EventWriter<TreeSpawnEvent>,
+) {
+    for (entity, mut boss, tree, apple_weights, boss_t) in bosses.iter_mut() {
+        boss.attack.tick(time.delta());
+        if !boss.attack.just_finished() {
+            continue;
+        }
+
+        let attack = boss.phase.attack(boss.attacks);
+        boss.attacks += 1;
+
+        match attack {
+            BossAttack::Barrage => {
+                let top = boss_t.translation
+                    + Vec3::Y * (TREE_STARTING_HEIGHT * boss_t.scale.y + APPLE_RADIUS * 2.0);
+                for i in 0..BARRAGE_APPLES {
+                    let (offset, velocity) = VolleyPattern::Burst.launch(
+                        i,
+                        BARRAGE_APPLES,
+                        top,
+                        Some(tractor.translation),
+                    );
+                    apple_writer.write(AppleSpawnEvent {
+                        at: top + offset * BARRAGE_SPREAD,
+                        apple_strength: AppleStrength::from_tree_level(tree.level / 2),
+                        kind: apple_weights.pick(),
+                        velocity,
+                    });
+                }
+            }
+            BossAttack::RootSlam => {
+                commands.spawn((
+                    Name::new("RootSlam"),
+                    RootSlam {
+                        timer: Timer::new(
+                            Duration::from_millis(ROOT_SLAM_DELAY_MS),
+                            TimerMode::Once,
+                        ),
+                        boss: entity,
+                    },
+                    StateScoped(Screen::InGame),
+                    Mesh3d(meshes.add(Cylinder::new(ROOT_SLAM_RADIUS, 0.1))),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: RED_500.with_alpha(0.4).into(),
+                        alpha_mode: AlphaMode::Blend,
+                        unlit: true,
+                        ..default()
+                    })),
+                    Transform::from_translation(tractor.translation),
+                    Animator::new(Tween::new(
+                        EaseFunction::QuadraticIn,
+                        Duration::from_millis(ROOT_SLAM_DELAY_MS),
+                        TransformScaleLens {
+                            start: Vec3::splat(0.1),
+                            end: Vec3::ONE,
+                        },
+                    )),
+                ));
+            }
+            BossAttack::Saplings => {
+                for _ in 0..SAPLINGS {
+                    let angle = rand::random::<f32>() * 2.0 * PI;
+                    let distance = SAPLING_DISTANCE.0
+                        + rand::random::<f32>() * (SAPLING_DISTANCE.1 - SAPLING_DISTANCE.0);
+                    let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * distance;
+                    tree_writer.write(TreeSpawnEvent {
+                        position: (boss_t.translation + offset).with_y(1000.),
+                        startlevel: 0,
+                        boss: false,
+                        species: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn root_slams(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut slams: Query<(Entity, &mut RootSlam, &Transform)>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
+) {
+    for (entity, mut slam, transform) in slams.iter_mut() {
+        slam.timer.tick(time.delta());
+        if !slam.timer.just_finished() {
+            continue;
+        }
+
+        explosion_writer.write(ExplosionEvent {
+            position: transform.translation,
+            radius: ROOT_SLAM_RADIUS,
+            damage: ROOT_SLAM_DAMAGE,
+            source: Some(slam.boss),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+fn boss_death(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    bosses: Query<&Transform, With<Boss>>,
+    mut score: ResMut<ScoreCounter>,
+    mut currency: ResMut<Currency>,
+    mut schedule: ResMut<BossSchedule>,
+) {
+    let Ok(boss_t) = bosses.get(trigger.target()) else {
+        return;
+    };
+
+    score.points += BOSS_REWARD_POINTS;
+    currency.add(BOSS_REWARD_UPGRADES);
+    schedule.timer.reset();
+
+    commands.spawn((
+        Name::new("BossDeathSequence"),
+        BossDeathSequence {
+            timer: Timer::new(
+                Duration::from_millis(DEATH_BURST_INTERVAL_MS),
+                TimerMode::Repeating,
+            ),
+            bursts: DEATH_BURSTS,
+        },
+        StateScoped(Screen::InGame),
+        Transform::from_translation(boss_t.translation),
+    ));
+}
+
+/// Bursts of particles all over where the boss stood
+fn death_sequences(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sequences: Query<(Entity, &mut BossDeathSequence, &Transform)>,
+) {
+    for (entity, mut sequence, transform) in sequences.iter_mut() {
+        sequence.timer.tick(time.delta());
+        if !sequence.timer.just_finished() {
+            continue;
+        }
+
+        let offset = Vec3::new(
+            rand::random::<f32>() - 0.5,
+            rand::random::<f32>(),
+            rand::random::<f32>() - 0.5,
+        ) * TREE_STARTING_HEIGHT
+            * 3.0;
+        commands.spawn((
+            apple_death_particles(),
+            Transform::from_translation(transform.translation + offset)
+                .with_scale(Vec3::splat(3.0)),
+        ));
+
+        sequence.bursts = sequence.bursts.saturating_sub(1);
+        if sequence.bursts == 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn boss_healthbar() -> impl Bundle {
+    (
+        Name::new("BossHealthBar"),
+        BossHealthBar,
+        StateScoped(Screen::InGame),
+        Node {
+            top: Val::Percent(9.0),
+            justify_self: JustifySelf::Center,
+            width: Val::Percent(50.),
+            height: Val::Percent(2.),
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        BorderRadius::all(Val::Px(4.)),
+        Outline::new(Val::Px(3.), Val::Px(0.), WHITE_SMOKE.into()),
+        BackgroundColor(BLACK.with_alpha(0.6).into()),
+        children![
+            (
+                BossHealthBarFill,
+                BackgroundColor(AMBER_700.into()),
+                Node {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    ..Default::default()
+                },
+            ),
+            (
+                Text::new("Ancient Tree"),
+                TextFont::from_font_size(14.),
+                TextColor(WHITE.into()),
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Percent(110.),
+                    ..Default::default()
+                },
+            )
+        ],
+    )
+}
+
+fn update_boss_healthbar(
+    mut commands: Commands,
+    bosses: Query<&Health, With<Boss>>,
+    bars: Query<Entity, With<BossHealthBar>>,
+    mut fills: Query<&mut Node, With<BossHealthBarFill>>,
+) {
+    let Some(health) = bosses.iter().next() else {
+        for bar in bars.iter() {
+            commands.entity(bar).despawn();
+        }
+        return;
+    };
+
+    if bars.is_empty() {
+        commands.spawn(boss_healthbar());
+    }
+
+    for mut fill in fills.iter_mut() {
+        fill.width = Val::Percent(health.percentage() as f32);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::{
     PausableSystems,
     audio::sound_effect,
-    gameplay::apple::{APPLE_RADIUS, Apple},
+    gameplay::{
+        GameLayer,
+        apple::{APPLE_RADIUS, Apple},
+        health::Critical,
+    },
     screens::Screen,
 };
+use bevy::ecs::system::SystemParam;
 use bevy_inspector_egui::egui::debug_text::print;
 use std::time::Duration;
 
@@ -15,6 +20,10 @@ pub struct BulletSpawnEvent {
     pub dir: Dir3,
     pub speed: f32,
     pub bullet: Bullet,
+    /// who fired the bullet
+    pub owner: Option<Entity>,
+    /// entities the bullet flies through
+    pub ignore: Vec<Entity>,
 }
 
 #[derive(Event)]
@@ -23,8 +32,38 @@ pub struct BulletSplitEvent {
     pub center: Vec3,
     // which bullets
     pub bullet: Bullet,
-    // apple radius
+    // apple radius, in world units
     pub radius: f32,
+    // the apple that was hit, the split bullets will not hit it again
+    pub source: Entity,
+    // who fired the bullet that split
+    pub owner: Option<Entity>,
+}
+
+/// The entity that fired a projectile, damage dealt by the projectile is attributed to it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ProjectileOwner(pub Entity);
+
+/// Entities a projectile passes through without colliding
+#[derive(Component, Debug, Clone, Default)]
+pub struct ProjectileIgnore(pub Vec<Entity>);
+
+/// Filters out contacts between projectiles and the entities they should ignore
+#[derive(SystemParam)]
+pub struct ProjectileCollisionHooks<'w, 's> {
+    ignores: Query<'w, 's, &'static ProjectileIgnore>,
+}
+
+impl CollisionHooks for ProjectileCollisionHooks<'_, '_> {
+    fn filter_pairs(&self, collider1: Entity, collider2: Entity, _commands: &mut Commands) -> bool {
+        let ignored = |projectile: Entity, other: Entity| {
+            self.ignores
+                .get(projectile)
+                .is_ok_and(|ignore| ignore.0.contains(&other))
+        };
+
+        !ignored(collider1, collider2) && !ignored(collider2, collider1)
+    }
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
@@ -81,13 +120,13 @@ fn fire_bullet_event_handler(
 ) {
     for evt in spawnevent.read() {
         if evt.bullet.damage > 0 {
-            commands.spawn(bullet(
-                &assets,
-                evt.bullet.clone(),
-                evt.at,
-                evt.dir,
-                evt.speed,
+            let mut bullet_commands = commands.spawn((
+                bullet(&assets, evt.bullet.clone(), evt.at, evt.dir, evt.speed),
+                ProjectileIgnore(evt.ignore.clone()),
             ));
+            if let Some(owner) = evt.owner {
+                bullet_commands.insert(ProjectileOwner(owner));
+            }
             commands.spawn(sound_effect(assets.sound.clone()));
         }
     }
@@ -97,39 +136,38 @@ pub const BULLET_SPEED: f32 = 70.;
 
 #[cfg_attr(feature = "dev_native", hot)]
 fn bullet_split_event_handler(
-    apples: Query<(&Transform, &LinearVelocity), With<Apple>>,
+    apples: Query<(Entity, &Transform, &LinearVelocity), With<Apple>>,
     mut split_event: EventReader<BulletSplitEvent>,
     mut spawn_writer: EventWriter<BulletSpawnEvent>,
 ) {
     for evt in split_event.read() {
-        let apples = apples
+        let targets = apples
             .iter()
             .sort_by::<&Transform>(|t1, t2| {
                 t1.translation
                     .distance_squared(evt.center)
                     .total_cmp(&t2.translation.distance_squared(evt.center))
             })
-            .take(3);
-
-        let mut iter = apples.into_iter();
-        iter.next();
-
-        for (apple_t, apple_v) in iter {
-            let apple_target = apple_t.translation
-                + apple_v.0 * (apple_t.translation.distance(evt.center) / BULLET_SPEED);
-
-            let distance = (apple_t.translation - evt.center).length_squared();
-
-            if distance > evt.radius + 0.0005 {
-                if let Ok(dir) = (apple_target - evt.center).normalize().try_into() {
-                    spawn_writer.write(BulletSpawnEvent {
-                        at: evt.center + dir * evt.radius,
-                        dir,
-                        bullet: evt.bullet.clone(),
-                        speed: BULLET_SPEED,
-                        //     TODO Add apple entity spawning the new bullet so we can later can check so it is not killed by the new bullet
-                    });
-                }
+            .filter(|(apple, _, _)| *apple != evt.source)
+            .take(2);
+
+        for (_apple, apple_t, apple_v) in targets {
+            let distance = apple_t.translation.distance(evt.center);
+            if distance <= evt.radius {
+                continue;
+            }
+
+            let apple_target = apple_t.translation + apple_v.0 * (distance / BULLET_SPEED);
+
+            if let Ok(dir) = (apple_target - evt.center).normalize().try_into() {
+                spawn_writer.write(BulletSpawnEvent {
+                    at: evt.center + dir * evt.radius,
+                    dir,
+                    bullet: evt.bullet.clone(),
+                    speed: BULLET_SPEED,
+                    owner: evt.owner,
+                    ignore: vec![evt.source],
+                });
             }
         }
     }
@@ -139,6 +177,7 @@ fn bullet_split_event_handler(
 pub struct Bullet {
     pub damage: u32,
     pub split_probability: f32,
+    pub critical: Critical,
 }
 
 impl Bullet {
@@ -146,13 +185,21 @@ impl Bullet {
         Self {
             damage,
             split_probability,
+            critical: Critical::NONE,
         }
     }
+
+    pub fn with_critical(mut self, critical: Critical) -> Self {
+        self.critical = critical;
+        self
+    }
+
     /// returns a bullet with half the values of the original
     pub fn split(&self) -> Self {
         Bullet {
             damage: self.damage / 2,
             split_probability: 1.,
+            critical: self.critical,
         }
     }
 }
@@ -183,5 +230,8 @@ pub fn bullet(
         },
         CollisionEventsEnabled,
         CollidingEntities::default(),
+        ActiveCollisionHooks::FILTER_PAIRS,
+        // bullets only hit the world, not the tractor or seeds in flight
+        CollisionLayers::new(GameLayer::Bullet, [GameLayer::Default]),
     )
 }
@@ -1,25 +1,54 @@
+use crate::Pause;
 use crate::gameplay::{
-    level::Ground,
-    tractor::{TRACTOR_LENGTH, Tractor, Wheel},
-    turret::{BARREL_LEN, Turret},
+    ability::AbilitySlot,
+    flip::RightVehicle,
+    tractor::Tractor,
+    turret::{BARREL_LEN, Turret, TurretMagazine},
+    vehicle::VehicleControls,
 };
 
 use super::*;
 use bevy_enhanced_input::prelude::*;
 use std::collections::HashSet;
-use tractor::{LeftWheels, RightWheels};
 
-const TRACTOR_ACCELERATION: f32 = 10000.0;
-const TRACTOR_TURN_RATE: f32 = 130.0;
+/// -1 full reverse to 1 full forward
+#[derive(Debug, InputAction)]
+#[input_action(output = f32)]
+struct ThrottleEvent;
+
+/// -1 left to 1 right
+#[derive(Debug, InputAction)]
+#[input_action(output = f32)]
+struct SteerEvent;
 
+/// Slows the wheels down gently
 #[derive(Debug, InputAction)]
-#[input_action(output = Vec3)]
-struct MoveEvent;
+#[input_action(output = bool)]
+struct BrakeEvent;
+
+/// Locks the wheels
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct HandbrakeEvent;
 
 #[derive(Debug, InputAction)]
 #[input_action(output = bool)]
 pub struct FireEvent;
 
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct ReloadEvent;
+
+/// Uses the vehicle's ability
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct AbilityEvent;
+
+/// Turns an overturned vehicle back on its wheels
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct FlipBackEvent;
+
 #[derive(InputContext)]
 pub struct InTractor;
 
@@ -27,23 +56,69 @@ pub(super) fn plugin(app: &mut App) {
     debug!("Adding movement controls plugin");
 
     app.add_plugins(EnhancedInputPlugin);
-    app.add_systems(Update, tractor_break);
 
     app.add_input_context::<InTractor>()
         .add_observer(bind_actions)
-        .add_observer(tractor_move)
+        .add_observer(throttle)
+        .add_observer(release_throttle)
+        .add_observer(steer)
+        .add_observer(release_steer)
+        .add_observer(brake)
+        .add_observer(release_brake)
+        .add_observer(handbrake)
+        .add_observer(release_handbrake)
         .add_observer(fire_turret)
-        .add_observer(stop_firing_turret);
+        .add_observer(stop_firing_turret)
+        .add_observer(reload_turret)
+        .add_observer(use_ability)
+        .add_observer(flip_back);
 }
 
 fn bind_actions(trigger: Trigger<Binding<InTractor>>, mut actions: Query<&mut Actions<InTractor>>) {
     debug!("Binding actions");
     let mut actions = actions.get_mut(trigger.target()).unwrap();
+    actions.bind::<ThrottleEvent>().to((
+        Bidirectional {
+            positive: KeyCode::KeyW,
+            negative: KeyCode::KeyS,
+        },
+        Bidirectional {
+            positive: KeyCode::ArrowUp,
+            negative: KeyCode::ArrowDown,
+        },
+        GamepadAxis::LeftStickY.with_modifiers(DeadZone::default()),
+    ));
+    actions.bind::<SteerEvent>().to((
+        Bidirectional {
+            positive: KeyCode::KeyD,
+            negative: KeyCode::KeyA,
+        },
+        Bidirectional {
+            positive: KeyCode::ArrowRight,
+            negative: KeyCode::ArrowLeft,
+        },
+        GamepadAxis::LeftStickX.with_modifiers(DeadZone::default()),
+    ));
     actions
-        .bind::<MoveEvent>()
-        .to(Spatial::wasd_and(KeyCode::ArrowUp, KeyCode::ArrowDown));
+        .bind::<BrakeEvent>()
+        .to((KeyCode::KeyC, GamepadButton::LeftThumb));
+    actions
+        .bind::<HandbrakeEvent>()
+        .to((KeyCode::KeyX, GamepadButton::RightThumb));
     actions.bind::<FireEvent>().to(KeyCode::Space);
     actions.bind::<FireEvent>().to(MouseButton::Left);
+    actions.bind::<FireEvent>().to(GamepadButton::RightTrigger2);
+    actions
+        .bind::<ReloadEvent>()
+        .to((KeyCode::KeyR, GamepadButton::RightTrigger));
+    actions.bind::<AbilityEvent>().to(KeyCode::ShiftLeft);
+    actions.bind::<AbilityEvent>().to(MouseButton::Right);
+    actions
+        .bind::<AbilityEvent>()
+        .to(GamepadButton::LeftTrigger2);
+    actions
+        .bind::<FlipBackEvent>()
+        .to((KeyCode::KeyF, GamepadButton::Select));
 }
 
 fn fire_turret(
@@ -85,73 +160,111 @@ fn stop_firing_turret(
     }
 }
 
-fn tractor_break(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut angular_velocity: Query<&mut AngularVelocity>,
-    mut torque: Query<&mut ExternalTorque>,
-    query: Query<(&Tractor, &LeftWheels, &RightWheels)>,
-    // mut tractor: Single<&mut ExternalForce, With<Tractor>>,
+/// Starts a reload, or tries an active reload when already reloading
+fn reload_turret(
+    trigger: Trigger<Started<ReloadEvent>>,
+    tractors: Query<&Children, With<Tractor>>,
+    mut magazines: Query<&mut TurretMagazine>,
 ) {
-    if keyboard.any_pressed([KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyD, KeyCode::KeyA]) {
+    let Ok(tractor_children) = tractors.get(trigger.target()) else {
         return;
     };
 
-    let Ok((_, left_wheels, right_wheels)) = query.single() else {
-        debug!("No tractor found, skipping brake application");
-        return;
-    };
+    for child in tractor_children {
+        if let Ok(mut magazine) = magazines.get_mut(*child) {
+            if magazine.reloading {
+                magazine.active_reload();
+            } else {
+                magazine.start_reload();
+            }
+        }
+    }
+}
 
-    for wheel in left_wheels.iter() {
-        *angular_velocity.get_mut(wheel).unwrap() = AngularVelocity::ZERO;
-        torque.get_mut(wheel).unwrap().clear();
+fn use_ability(
+    trigger: Trigger<Started<AbilityEvent>>,
+    mut commands: Commands,
+    mut slots: Query<&mut AbilitySlot>,
+    pause: Res<State<Pause>>,
+) {
+    // the input stays bound in the pause menu
+    if pause.get().0 {
+        return;
     }
 
-    for wheel in right_wheels.iter() {
-        *angular_velocity.get_mut(wheel).unwrap() = AngularVelocity::ZERO;
-        torque.get_mut(wheel).unwrap().clear();
+    if let Ok(mut slot) = slots.get_mut(trigger.target()) {
+        slot.try_use(&mut commands, trigger.target());
     }
 }
 
-fn tractor_move(
-    trigger: Trigger<Fired<MoveEvent>>,
-    tractor: Single<(&mut ExternalForce, &mut AngularVelocity, &Transform), With<Tractor>>,
-    wheels: Query<Entity, With<Wheel>>,
-    ground_entity: Single<Entity, With<Ground>>,
-    time: Res<Time>,
-    collisions: Collisions,
+fn flip_back(
+    trigger: Trigger<Started<FlipBackEvent>>,
+    mut commands: Commands,
+    pause: Res<State<Pause>>,
 ) {
-    let (mut force, mut angular_velocity, transform) = tractor.into_inner();
+    if pause.get().0 {
+        return;
+    }
 
-    let mut wheels_on_ground = 0;
+    commands.trigger_targets(RightVehicle { manual: true }, trigger.target());
+}
 
-    for wheel in wheels.iter() {
-        if let Some(collision) = collisions.get(*ground_entity, wheel) {
-            if collision.is_touching() {
-                wheels_on_ground += 1;
-            }
-        };
-        if wheels_on_ground >= 2 {
-            break;
-        }
+fn throttle(trigger: Trigger<Fired<ThrottleEvent>>, mut controls: Query<&mut VehicleControls>) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.throttle = trigger.value;
     }
+}
 
-    angular_velocity.x =
-        -transform.up().x * trigger.value.x * time.delta_secs() * TRACTOR_TURN_RATE;
-    angular_velocity.y =
-        -transform.up().y * trigger.value.x * time.delta_secs() * TRACTOR_TURN_RATE;
-    angular_velocity.z =
-        -transform.up().z * trigger.value.x * time.delta_secs() * TRACTOR_TURN_RATE;
+fn release_throttle(
+    trigger: Trigger<Completed<ThrottleEvent>>,
+    mut controls: Query<&mut VehicleControls>,
+) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.throttle = 0.0;
+    }
+}
 
-    if wheels_on_ground < 2 {
-        return;
+fn steer(trigger: Trigger<Fired<SteerEvent>>, mut controls: Query<&mut VehicleControls>) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.steer = trigger.value;
     }
+}
 
-    let apply_force = -trigger.value.z * time.delta_secs() * TRACTOR_ACCELERATION;
-    let forward = transform.forward().normalize();
+fn release_steer(
+    trigger: Trigger<Completed<SteerEvent>>,
+    mut controls: Query<&mut VehicleControls>,
+) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.steer = 0.0;
+    }
+}
 
-    let down_force = -Vec3::Y * TRACTOR_ACCELERATION * time.delta_secs();
+fn brake(trigger: Trigger<Started<BrakeEvent>>, mut controls: Query<&mut VehicleControls>) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.brake = true;
+    }
+}
 
-    force
-        .set_force(forward * apply_force + down_force)
-        .with_persistence(false);
+fn release_brake(
+    trigger: Trigger<Completed<BrakeEvent>>,
+    mut controls: Query<&mut VehicleControls>,
+) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.brake = false;
+    }
+}
+
+fn handbrake(trigger: Trigger<Started<HandbrakeEvent>>, mut controls: Query<&mut VehicleControls>) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.handbrake = true;
+    }
+}
+
+fn release_handbrake(
+    trigger: Trigger<Completed<HandbrakeEvent>>,
+    mut controls: Query<&mut VehicleControls>,
+) {
+    if let Ok(mut controls) = controls.get_mut(trigger.target()) {
+        controls.handbrake = false;
+    }
 }
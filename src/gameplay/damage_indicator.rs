@@ -1,63 +1,166 @@
-use std::time::Duration;
+use std::collections::HashMap;
 
-use crate::gameplay::health::DamageEvent;
+use crate::gameplay::health::{DamageDealt, DamageKind};
 use bevy::prelude::*;
-use bevy_tweening::{Animator, Tween, lens::TransformPositionLens};
 use bevy_ui_anchor::*;
 
 use super::*;
 
+/// How long a number stays after the last hit that was added to it
+const NUMBER_LIFETIME_SEC: f32 = 0.7;
+/// Hits on the same target within this time are added to the same number
+const AGGREGATE_WINDOW_SEC: f32 = 0.35;
+const RISE_SPEED: f32 = 4.0;
+const MAX_RISE: f32 = 4.0;
+const POP_DURATION_SEC: f32 = 0.15;
+const POP_SCALE: f32 = 0.6;
+const FONT_SIZE: f32 = 18.0;
+const CRIT_FONT_SIZE: f32 = 28.0;
+
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, spawn_damage_indicators_on_event);
+    app.add_systems(
+        Update,
+        (spawn_damage_indicators_on_event, animate_damage_indicators).chain(),
+    );
 }
 
 #[derive(Component)]
 pub struct DamageIndicatorBase;
 
+/// A damage number rising above the entity it belongs to
+#[derive(Component, Debug)]
+pub struct DamageNumber {
+    target: Entity,
+    /// last known position of the target
+    anchor: Vec3,
+    total: u32,
+    kind: DamageKind,
+    crit: bool,
+    age: f32,
+    since_hit: f32,
+}
+
+impl DamageNumber {
+    fn text(&self) -> String {
+        if self.crit {
+            format!("{}!", self.total)
+        } else {
+            format!("{}", self.total)
+        }
+    }
+
+    fn color(&self) -> Color {
+        if self.crit {
+            return RED.into();
+        }
+
+        match self.kind {
+            DamageKind::Bullet => ORANGE.into(),
+            DamageKind::Saw => YELLOW.into(),
+            DamageKind::Impact => WHITE.into(),
+            DamageKind::Explosion => ORANGE_RED.into(),
+            DamageKind::Fall => GRAY.into(),
+        }
+    }
+
+    /// pops up to a larger size on every hit, then settles
+    fn font_size(&self) -> f32 {
+        let size = if self.crit { CRIT_FONT_SIZE } else { FONT_SIZE };
+        let pop = (1.0 - self.since_hit / POP_DURATION_SEC).max(0.0);
+
+        size * (1.0 + POP_SCALE * pop)
+    }
+}
+
 #[cfg_attr(feature = "dev_native", hot)]
 fn spawn_damage_indicators_on_event(
     mut commands: Commands,
     transforms: Query<&GlobalTransform>,
-    mut damage_reader: EventReader<DamageEvent>,
+    mut damage_reader: EventReader<DamageDealt>,
+    mut numbers: Query<&mut DamageNumber>,
 ) {
-    const DUR: u64 = 500;
+    let mut hits: HashMap<Entity, DamageNumber> = HashMap::new();
+
     for damage in damage_reader.read() {
-        if let Ok(position) = transforms.get(damage.entity) {
-            let dir: Vec3 = rand::random();
-            let len = 3.0;
-            let dir = dir.normalize() * len;
-            let tween = Tween::new(
-                // Use a quadratic easing on both endpoints.
-                EaseFunction::Linear,
-                // Animation time (one way only; for ping-pong it takes 2 seconds
-                // to come back to start).
-                Duration::from_millis(DUR),
-                // The lens gives the Animator access to the Transform component,
-                // to animate it. It also contains the start and end values associated
-                // with the animation ratios 0. and 1.
-                TransformPositionLens {
-                    start: position.translation(),
-                    end: position.translation() + dir,
-                },
-            );
-
-            commands.spawn((
-                Name::new("DamageIndicator"),
-                DamageIndicatorBase,
-                Animator::new(tween),
-                DespawnAfter::millis(DUR),
-                //
-                // Mesh3d(meshes.add(Sphere::new(1.0))),
-                // MeshMaterial3d(materials.add(StandardMaterial::from_color(RED))),
-                Transform::from_translation(position.translation()),
-                Visibility::Visible,
-                AnchoredUiNodes::spawn_one((
-                    DespawnAfter::millis(DUR),
-                    Name::new("DamageIndicatorUI"),
-                    Text::new(format!("{}", damage.value)),
-                    AnchorUiConfig::default(),
-                )),
-            ));
+        let Ok(position) = transforms.get(damage.entity) else {
+            continue;
+        };
+
+        let hit = hits.entry(damage.entity).or_insert(DamageNumber {
+            target: damage.entity,
+            anchor: position.translation(),
+            total: 0,
+            kind: damage.kind,
+            crit: false,
+            age: 0.0,
+            since_hit: 0.0,
+        });
+        hit.total += damage.value;
+        hit.kind = damage.kind;
+        hit.crit |= damage.crit;
+    }
+
+    // add to the numbers that are already rising from the same target
+    for mut number in numbers.iter_mut() {
+        if number.since_hit > AGGREGATE_WINDOW_SEC {
+            continue;
+        }
+        if let Some(hit) = hits.remove(&number.target) {
+            number.total += hit.total;
+            number.kind = hit.kind;
+            number.crit |= hit.crit;
+            number.since_hit = 0.0;
+        }
+    }
+
+    for number in hits.into_values() {
+        commands.spawn((
+            Name::new("DamageIndicator"),
+            DamageIndicatorBase,
+            Transform::from_translation(number.anchor),
+            Visibility::Visible,
+            AnchoredUiNodes::spawn_one((
+                Name::new("DamageIndicatorUI"),
+                Text::new(number.text()),
+                TextColor(number.color()),
+                TextFont::from_font_size(number.font_size()),
+                AnchorUiConfig::default(),
+            )),
+            number,
+        ));
+    }
+}
+
+fn animate_damage_indicators(
+    mut commands: Commands,
+    time: Res<Time>,
+    targets: Query<&GlobalTransform, Without<DamageNumber>>,
+    mut numbers: Query<(Entity, &mut DamageNumber, &mut Transform, &AnchoredUiNodes)>,
+    mut texts: Query<(&mut Text, &mut TextColor, &mut TextFont)>,
+) {
+    for (entity, mut number, mut transform, ui_nodes) in numbers.iter_mut() {
+        number.age += time.delta_secs();
+        number.since_hit += time.delta_secs();
+
+        if number.since_hit > NUMBER_LIFETIME_SEC {
+            for ui_node in ui_nodes.iter() {
+                commands.entity(ui_node).try_despawn();
+            }
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Ok(target) = targets.get(number.target) {
+            number.anchor = target.translation();
+        }
+        transform.translation = number.anchor + Vec3::Y * (number.age * RISE_SPEED).min(MAX_RISE);
+
+        for ui_node in ui_nodes.iter() {
+            if let Ok((mut text, mut color, mut font)) = texts.get_mut(ui_node) {
+                text.0 = number.text();
+                color.0 = number.color();
+                font.font_size = number.font_size();
+            }
         }
     }
 }
@@ -0,0 +1,217 @@
+use crate::PausableSystems;
+use crate::gameplay::health::Death;
+use crate::gameplay::tree::Tree;
+use crate::screens::Screen;
+use bevy::color::palettes::tailwind::{AMBER_900, GREEN_500};
+
+use super::*;
+
+/// Cells along each side of the grid
+const GRID_CELLS: usize = 27;
+const CELL_SIZE: f32 = 10.0;
+const HALF_EXTENT: f32 = GRID_CELLS as f32 * CELL_SIZE / 2.0;
+/// What the soil settles back to without trees around
+const BASE_FERTILITY: f32 = 0.2;
+const RECOVERY_PER_SEC: f32 = 0.01;
+/// Added to the cell of a tree every second per level, neighbours get half of it
+const ENRICH_PER_LEVEL_PER_SEC: f32 = 0.004;
+/// What is left of the soil where a tree was cut down
+const CLEARED_FERTILITY: f32 = 0.0;
+const HEATMAP_CELL_PX: f32 = 6.0;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Fertility>();
+
+    app.add_systems(
+        OnEnter(Screen::InGame),
+        (
+            |mut commands: Commands| commands.insert_resource(Fertility::default()),
+            spawn_heatmap,
+        ),
+    );
+    app.add_observer(clear_soil);
+
+    app.add_systems(
+        Update,
+        (enrich_soil, toggle_heatmap, update_heatmap)
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// How likely trees are to grow on each part of the map
+#[derive(Resource, Debug)]
+pub struct Fertility {
+    cells: Vec<f32>,
+}
+
+impl Default for Fertility {
+    fn default() -> Self {
+        Self {
+            cells: vec![BASE_FERTILITY; GRID_CELLS * GRID_CELLS],
+        }
+    }
+}
+
+impl Fertility {
+    fn cell(position: Vec3) -> Option<(usize, usize)> {
+        let x = ((position.x + HALF_EXTENT) / CELL_SIZE).floor();
+        let z = ((position.z + HALF_EXTENT) / CELL_SIZE).floor();
+        let range = 0.0..GRID_CELLS as f32;
+
+        (range.contains(&x) && range.contains(&z)).then_some((x as usize, z as usize))
+    }
+
+    pub fn get(&self, position: Vec3) -> f32 {
+        Self::cell(position).map_or(0.0, |(x, z)| self.cells[z * GRID_CELLS + x])
+    }
+
+    fn add(&mut self, x: usize, z: usize, amount: f32) {
+        let value = &mut self.cells[z * GRID_CELLS + x];
+        *value = (*value + amount).clamp(0.0, 1.0);
+    }
+
+    /// Enriches the cell and spreads half of it to the neighbouring cells
+    fn enrich(&mut self, position: Vec3, amount: f32) {
+        let Some((x, z)) = Self::cell(position) else {
+            return;
+        };
+
+        self.add(x, z, amount);
+        for (dx, dz) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, nz) = (x as i32 + dx, z as i32 + dz);
+            if (0..GRID_CELLS as i32).contains(&nx) && (0..GRID_CELLS as i32).contains(&nz) {
+                self.add(nx as usize, nz as usize, amount / 2.0);
+            }
+        }
+    }
+
+    fn clear(&mut self, position: Vec3) {
+        if let Some((x, z)) = Self::cell(position) {
+            self.cells[z * GRID_CELLS + x] = CLEARED_FERTILITY;
+        }
+    }
+
+    /// Random spot on the map, fertile cells are much more likely
+    pub fn sample_position(&self) -> Vec3 {
+        let weights = self.cells.iter().map(|fertility| fertility * fertility);
+        let total: f32 = weights.clone().sum();
+        let mut roll = rand::random::<f32>() * total;
+
+        let index = weights
+            .enumerate()
+            .find_map(|(index, weight)| {
+                roll -= weight;
+                (roll <= 0.0).then_some(index)
+            })
+            .unwrap_or_else(|| rand::random::<usize>() % self.cells.len());
+
+        let (x, z) = (index % GRID_CELLS, index / GRID_CELLS);
+        Vec3::new(
+            (x as f32 + rand::random::<f32>()) * CELL_SIZE - HALF_EXTENT,
+            1000.,
+            (z as f32 + rand::random::<f32>()) * CELL_SIZE - HALF_EXTENT,
+        )
+    }
+}
+
+fn enrich_soil(
+    time: Res<Time>,
+    trees: Query<(&Tree, &Transform)>,
+    mut fertility: ResMut<Fertility>,
+) {
+    let delta = time.delta_secs();
+
+    // everything drifts back to the base fertility
+    for value in fertility.cells.iter_mut() {
+        let step = RECOVERY_PER_SEC * delta;
+        *value += (BASE_FERTILITY - *value).clamp(-step, step);
+    }
+
+    for (tree, transform) in trees.iter() {
+        fertility.enrich(
+            transform.translation,
+            ENRICH_PER_LEVEL_PER_SEC * (tree.level + 1) as f32 * delta,
+        );
+    }
+}
+
+fn clear_soil(
+    trigger: Trigger<Death>,
+    trees: Query<&Transform, With<Tree>>,
+    mut fertility: ResMut<Fertility>,
+) {
+    if let Ok(transform) = trees.get(trigger.target()) {
+        fertility.clear(transform.translation);
+    }
+}
+
+#[derive(Component)]
+struct Heatmap;
+
+#[derive(Component)]
+struct HeatmapCell(usize);
+
+fn spawn_heatmap(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("FertilityHeatmap"),
+            Heatmap,
+            StateScoped(Screen::InGame),
+            Visibility::Hidden,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Percent(3.),
+                bottom: Val::Percent(3.),
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(GRID_CELLS as u16, HEATMAP_CELL_PX),
+                grid_template_rows: RepeatedGridTrack::px(GRID_CELLS as u16, HEATMAP_CELL_PX),
+                ..Default::default()
+            },
+            Outline::new(Val::Px(2.), Val::Px(0.), WHITE.into()),
+        ))
+        .with_children(|heatmap| {
+            for index in 0..GRID_CELLS * GRID_CELLS {
+                heatmap.spawn((
+                    HeatmapCell(index),
+                    Node::default(),
+                    BackgroundColor(AMBER_900.into()),
+                ));
+            }
+        });
+}
+
+fn toggle_heatmap(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut heatmap: Query<&mut Visibility, With<Heatmap>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+
+    for mut visibility in heatmap.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_heatmap(
+    fertility: Res<Fertility>,
+    heatmap: Query<&Visibility, With<Heatmap>>,
+    mut cells: Query<(&HeatmapCell, &mut BackgroundColor)>,
+) {
+    if heatmap
+        .iter()
+        .all(|visibility| *visibility == Visibility::Hidden)
+    {
+        return;
+    }
+
+    let barren = Color::from(AMBER_900);
+    let fertile = Color::from(GREEN_500);
+    for (cell, mut color) in cells.iter_mut() {
+        color.0 = barren.mix(&fertile, fertility.cells[cell.0]);
+    }
+}
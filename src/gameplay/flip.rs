@@ -0,0 +1,163 @@
+use crate::PausableSystems;
+use crate::gameplay::health::{DamageEvent, DamageKind, Health};
+use crate::gameplay::vehicle::VehicleControls;
+use crate::screens::Screen;
+
+use super::*;
+
+/// The body counts as overturned when its up points less upwards than this
+const OVERTURNED_UP_DOT: f32 = 0.3;
+/// How long the body has to stay tipped over, so a wild landing isn't a flip
+const OVERTURNED_CONFIRM_SECS: f32 = 1.0;
+/// Overturned vehicles right themselves after this long, so a run never gets stuck
+const AUTO_RIGHT_SECS: f32 = 6.0;
+const RIGHTING_SECS: f32 = 0.8;
+/// Upwards speed the body gets to come off the ground while turning
+const RIGHTING_HOP: f32 = 6.0;
+/// Damage taken for flipping back by hand, a flip shouldn't be free but never ends the run
+const RIGHTING_DAMAGE: u32 = 1;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (detect_overturned, auto_right, right_vehicles)
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(start_righting);
+}
+
+/// The vehicle lies on its side or roof, waiting to be turned back
+#[derive(Component, Debug)]
+pub struct Overturned {
+    /// rights the vehicle when it runs out
+    pub auto_right: Timer,
+}
+
+/// The vehicle is tipped over, it becomes [`Overturned`] when it stays that way
+#[derive(Component, Debug)]
+struct Tipped(Timer);
+
+/// The vehicle is being turned back on its wheels
+#[derive(Component, Debug)]
+struct Righting(Timer);
+
+/// Triggered on a vehicle to turn it back on its wheels, does nothing when it isn't overturned
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RightVehicle {
+    /// asked for by the driver, only those cost health
+    pub manual: bool,
+}
+
+fn detect_overturned(
+    mut commands: Commands,
+    mut vehicles: Query<
+        (Entity, &Transform, Option<&mut Tipped>, Has<Overturned>),
+        (With<VehicleControls>, Without<Righting>),
+    >,
+    time: Res<Time>,
+) {
+    for (entity, transform, tipped, overturned) in vehicles.iter_mut() {
+        if transform.up().dot(Vec3::Y) >= OVERTURNED_UP_DOT {
+            if tipped.is_some() || overturned {
+                commands.entity(entity).remove::<(Tipped, Overturned)>();
+            }
+            continue;
+        }
+
+        if overturned {
+            continue;
+        }
+
+        match tipped {
+            None => {
+                commands.entity(entity).insert(Tipped(Timer::from_seconds(
+                    OVERTURNED_CONFIRM_SECS,
+                    TimerMode::Once,
+                )));
+            }
+            Some(mut tipped) => {
+                if tipped.0.tick(time.delta()).finished() {
+                    commands
+                        .entity(entity)
+                        .remove::<Tipped>()
+                        .insert(Overturned {
+                            auto_right: Timer::from_seconds(AUTO_RIGHT_SECS, TimerMode::Once),
+                        });
+                }
+            }
+        }
+    }
+}
+
+fn auto_right(
+    mut commands: Commands,
+    mut vehicles: Query<(Entity, &mut Overturned), Without<Righting>>,
+    time: Res<Time>,
+) {
+    for (entity, mut overturned) in vehicles.iter_mut() {
+        if overturned.auto_right.tick(time.delta()).just_finished() {
+            commands.trigger_targets(RightVehicle { manual: false }, entity);
+        }
+    }
+}
+
+fn start_righting(
+    trigger: Trigger<RightVehicle>,
+    mut commands: Commands,
+    mut vehicles: Query<
+        (&mut LinearVelocity, Option<&Health>),
+        (With<Overturned>, Without<Righting>),
+    >,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    let Ok((mut velocity, health)) = vehicles.get_mut(trigger.target()) else {
+        return;
+    };
+
+    velocity.y += RIGHTING_HOP;
+    commands
+        .entity(trigger.target())
+        .insert(Righting(Timer::from_seconds(
+            RIGHTING_SECS,
+            TimerMode::Once,
+        )));
+
+    // the last point of health is never taken
+    let damage = health.map_or(0, |health| {
+        RIGHTING_DAMAGE.min(health.current.saturating_sub(1))
+    });
+    if !trigger.manual || damage == 0 {
+        return;
+    }
+
+    damage_writer.write(DamageEvent {
+        value: damage,
+        entity: trigger.target(),
+        source: None,
+        kind: DamageKind::Fall,
+        crit: false,
+    });
+}
+
+/// Spins the body so it's upright again when the righting ends, keeping its heading
+fn right_vehicles(
+    mut commands: Commands,
+    mut vehicles: Query<(Entity, &Transform, &mut AngularVelocity, &mut Righting)>,
+    time: Res<Time>,
+) {
+    for (entity, transform, mut angular, mut righting) in vehicles.iter_mut() {
+        if righting.0.tick(time.delta()).finished() {
+            angular.0 = Vec3::ZERO;
+            commands
+                .entity(entity)
+                .remove::<(Righting, Overturned, Tipped)>();
+            continue;
+        }
+
+        let (axis, angle) = Quat::from_rotation_arc(*transform.up(), Vec3::Y).to_axis_angle();
+        let remaining = righting.0.remaining_secs().max(time.delta_secs());
+        angular.0 = axis * angle / remaining;
+    }
+}
@@ -0,0 +1,236 @@
+use crate::PausableSystems;
+use crate::gameplay::ability::{Ability, AbilityUsed};
+use crate::gameplay::health::Death;
+use crate::gameplay::level::{PLANE_X_SIZE, PLANE_Z_SIZE, terrain_height};
+use crate::gameplay::pickup::{PICKUP_LIFETIME_MS, PickedUp, Pickup, PickupAssets, pickup};
+use crate::gameplay::tractor::Tractor;
+use crate::gameplay::tree::Tree;
+use crate::gameplay::vehicle::VehicleControls;
+use crate::gameplay::vehicle_kind::VehicleKind;
+use crate::screens::Screen;
+use bevy::color::palettes::tailwind::{ORANGE_700, ZINC_600};
+
+use super::*;
+
+/// Fuel burnt per second at full throttle
+const FUEL_BURN_PER_SEC: f32 = 1.5;
+const BOOST_FUEL: f32 = 8.0;
+/// How much of the motor's torque is left when the tank is empty
+const DRY_TORQUE_FACTOR: f32 = 0.2;
+const FUEL_CAN_AMOUNT: f32 = 35.0;
+const TREE_FUEL_CAN_CHANCE: f32 = 0.3;
+const DEPOT_REFILL_SECS: f32 = 20.0;
+const DEPOT_RADIUS: f32 = 2.5;
+/// Depots sit this far out from the middle, as a share of the map size
+const DEPOT_SPREAD: f32 = 0.3;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Fuel>();
+    app.init_resource::<FuelEnabled>();
+
+    app.add_systems(OnEnter(Screen::InGame), spawn_depots);
+    app.add_systems(
+        Update,
+        (burn_fuel, restock_depots)
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(fill_tank)
+        .add_observer(burn_boost_fuel)
+        .add_observer(refuel)
+        .add_observer(drop_fuel_can);
+}
+
+/// The tank of a vehicle, vehicles without one never run dry
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Fuel {
+    pub current: f32,
+    pub capacity: f32,
+}
+
+impl Fuel {
+    pub fn full(capacity: f32) -> Self {
+        Self {
+            current: capacity,
+            capacity,
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.current / self.capacity
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn burn(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    pub fn refill(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.capacity);
+    }
+
+    /// multiplied with the motor torque, the motor barely pushes on an empty tank
+    pub fn torque_factor(&self) -> f32 {
+        if self.is_empty() {
+            DRY_TORQUE_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Whether tractors have to mind their fuel, without it they never run dry
+#[derive(Resource, Debug)]
+pub struct FuelEnabled(pub bool);
+
+impl Default for FuelEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Puts out a fresh fuel can a while after the last one was taken
+#[derive(Component, Debug)]
+struct FuelDepot {
+    timer: Timer,
+    can: Option<Entity>,
+}
+
+/// Tractors start with a full tank of their vehicle's size, when fuel is on
+fn fill_tank(
+    trigger: Trigger<OnAdd, Tractor>,
+    mut commands: Commands,
+    vehicles: Query<&VehicleKind>,
+    enabled: Res<FuelEnabled>,
+) {
+    if !enabled.0 {
+        return;
+    }
+
+    if let Ok(kind) = vehicles.get(trigger.target()) {
+        commands
+            .entity(trigger.target())
+            .insert(Fuel::full(kind.spec().fuel));
+    }
+}
+
+fn burn_fuel(time: Res<Time>, mut vehicles: Query<(&VehicleControls, &mut Fuel)>) {
+    for (controls, mut fuel) in vehicles.iter_mut() {
+        let effort = controls.throttle.abs().max(controls.steer.abs()).min(1.0);
+        fuel.burn(effort * FUEL_BURN_PER_SEC * time.delta_secs());
+    }
+}
+
+fn burn_boost_fuel(trigger: Trigger<AbilityUsed>, mut tanks: Query<&mut Fuel>) {
+    if trigger.0 != Ability::Boost {
+        return;
+    }
+
+    if let Ok(mut fuel) = tanks.get_mut(trigger.target()) {
+        fuel.burn(BOOST_FUEL);
+    }
+}
+
+fn refuel(trigger: Trigger<PickedUp>, mut tanks: Query<&mut Fuel>) {
+    let Pickup::Fuel(amount) = trigger.0 else {
+        return;
+    };
+
+    if let Ok(mut fuel) = tanks.get_mut(trigger.target()) {
+        fuel.refill(amount);
+    }
+}
+
+/// Felled trees sometimes leave a fuel can behind
+fn drop_fuel_can(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    trees: Query<&Transform, With<Tree>>,
+    assets: Res<PickupAssets>,
+    enabled: Res<FuelEnabled>,
+) {
+    let Ok(transform) = trees.get(trigger.target()) else {
+        return;
+    };
+    if !enabled.0 {
+        return;
+    }
+
+    if rand::random::<f32>() < TREE_FUEL_CAN_CHANCE {
+        let position = transform.translation;
+        commands.spawn((
+            pickup(&assets, Pickup::Fuel(FUEL_CAN_AMOUNT), position),
+            DespawnAfter::millis(PICKUP_LIFETIME_MS),
+        ));
+    }
+}
+
+fn spawn_depots(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    enabled: Res<FuelEnabled>,
+) {
+    if !enabled.0 {
+        return;
+    }
+
+    let mesh = meshes.add(Cylinder::new(DEPOT_RADIUS, 0.2));
+    let material = materials.add(StandardMaterial::from_color(ZINC_600));
+    let stripe = materials.add(StandardMaterial::from_color(ORANGE_700));
+
+    let x = PLANE_X_SIZE * DEPOT_SPREAD;
+    let z = PLANE_Z_SIZE * DEPOT_SPREAD;
+    for (x, z) in [(x, z), (-x, z), (x, -z), (-x, -z)] {
+        let mut timer = Timer::from_seconds(DEPOT_REFILL_SECS, TimerMode::Once);
+        // the depots are stocked from the start
+        timer.tick(timer.duration());
+
+        commands.spawn((
+            Name::new("FuelDepot"),
+            FuelDepot { timer, can: None },
+            StateScoped(Screen::InGame),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x, terrain_height(x, z) + 0.1, z),
+            children![(
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(stripe.clone()),
+                Transform::from_xyz(0.0, 0.05, 0.0).with_scale(Vec3::new(0.8, 1.0, 0.8)),
+            )],
+        ));
+    }
+}
+
+fn restock_depots(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut depots: Query<(&Transform, &mut FuelDepot)>,
+    pickups: Query<(), With<Pickup>>,
+    assets: Res<PickupAssets>,
+) {
+    for (transform, mut depot) in depots.iter_mut() {
+        if depot.can.is_some_and(|can| pickups.contains(can)) {
+            continue;
+        }
+        if depot.can.take().is_some() {
+            // the can was just taken
+            depot.timer.reset();
+        }
+
+        if depot.timer.tick(time.delta()).finished() {
+            let can = commands
+                .spawn(pickup(
+                    &assets,
+                    Pickup::Fuel(FUEL_CAN_AMOUNT),
+                    transform.translation,
+                ))
+                .id();
+            depot.can = Some(can);
+        }
+    }
+}
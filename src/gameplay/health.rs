@@ -1,15 +1,30 @@
 use crate::PausableSystems;
-use crate::audio::sound_effect;
-use crate::gameplay::apple::{Apple, AppleStrength};
-use crate::gameplay::bullet::{Bullet, BulletSplitEvent};
+use crate::audio::{sound_effect, sound_effect_with};
+use crate::gameplay::ability::Shielded;
+use crate::gameplay::apple::{Apple, AppleStrength, apple_death_particles, release_apple};
+use crate::gameplay::apple_kind::AppleKind;
+use crate::gameplay::bullet::{Bullet, BulletSplitEvent, ProjectileOwner};
+use crate::gameplay::parts::{PartDamageEvent, TURRET_HIT_RANGE};
 use crate::gameplay::tractor::{LeftWheels, RightWheels, Tractor};
+use crate::gameplay::tree::Tree;
+use crate::gameplay::turret::Turret;
 use crate::screens::Screen;
-use avian3d::prelude::CollisionStarted;
+use avian3d::prelude::{CollisionStarted, ComputedMass, LinearVelocity};
 use bevy::prelude::*;
 use std::collections::HashSet;
 
 use super::*;
 
+/// Below this speed towards what it hits the tractor does not ram, it gets hit
+const RAM_MIN_SPEED: f32 = 4.0;
+/// At this speed towards what it hits the tractor rams at full strength
+const RAM_FULL_SPEED: f32 = 12.0;
+const RAM_DAMAGE_PER_IMPULSE: f32 = 0.2;
+/// How much less damage the tractor takes from an apple when ramming at full strength
+const RAM_SELF_DAMAGE_REDUCTION: f32 = 0.75;
+/// Impulse at which the impact sound plays at full volume
+const IMPACT_SOUND_FULL_IMPULSE: f32 = 60.0;
+
 #[derive(Resource)]
 struct HealthAssets {
     tractor_damage_sound: Handle<AudioSource>,
@@ -29,13 +44,18 @@ pub fn plugin(app: &mut App) {
     app.init_resource::<HealthAssets>();
 
     app.add_event::<DamageEvent>()
+        .add_event::<DamageDealt>()
         .add_event::<Death>()
+        .add_event::<ImpactEvent>()
+        .add_event::<ExplosionEvent>()
         .add_systems(
             Update,
             (
                 damage_health.run_if(in_state(Screen::InGame)),
                 damage_tractor.run_if(in_state(Screen::InGame)),
-                bullet_apple_collision_damage.run_if(in_state(Screen::InGame)),
+                play_impact_sounds.run_if(in_state(Screen::InGame)),
+                bullet_collision_damage.run_if(in_state(Screen::InGame)),
+                explode.run_if(in_state(Screen::InGame)),
             )
                 .in_set(PausableSystems),
         );
@@ -69,30 +89,213 @@ impl Health {
     }
 }
 
+/// How the damage was dealt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum DamageKind {
+    Bullet,
+    Saw,
+    Impact,
+    Explosion,
+    Fall,
+}
+
+/// Request to damage an entity, resistances and armor are applied before it reaches the health
 #[derive(Event, Debug)]
 pub struct DamageEvent {
     pub value: u32,
     pub entity: Entity,
+    /// the entity that dealt the damage, if any
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+    pub crit: bool,
+}
+
+/// Damage that was actually applied to an entity, after resistances and armor
+#[derive(Event, Debug)]
+pub struct DamageDealt {
+    pub value: u32,
+    pub entity: Entity,
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+    pub crit: bool,
 }
 
+/// Sent when the tractor runs into an apple or a tree
 #[derive(Event, Debug)]
-pub struct Death;
+pub struct ImpactEvent {
+    pub tractor: Entity,
+    pub other: Entity,
+    pub position: Vec3,
+    /// magnitude of the impulse between the tractor and what it hit
+    pub impulse: f32,
+    /// 0.0 when the tractor was hit, 1.0 when it rammed at full speed
+    pub ram: f32,
+}
+
+/// Damages everything with health around a point
+#[derive(Event, Debug)]
+pub struct ExplosionEvent {
+    pub position: Vec3,
+    pub radius: f32,
+    pub damage: u32,
+    /// the exploding entity, it is not damaged by its own explosion
+    pub source: Option<Entity>,
+}
+
+#[derive(Event, Debug)]
+pub struct Death {
+    /// the entity that dealt the killing blow, if any
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+}
+
+/// Multipliers for incoming damage per [`DamageKind`], 1.0 means full damage
+#[derive(Component, Debug, Clone)]
+pub struct Resistances {
+    pub bullet: f32,
+    pub saw: f32,
+    pub impact: f32,
+    pub explosion: f32,
+    pub fall: f32,
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self {
+            bullet: 1.0,
+            saw: 1.0,
+            impact: 1.0,
+            explosion: 1.0,
+            fall: 1.0,
+        }
+    }
+}
+
+impl Resistances {
+    pub fn with(mut self, kind: DamageKind, multiplier: f32) -> Self {
+        *self.multiplier_mut(kind) = multiplier;
+        self
+    }
+
+    pub fn multiplier(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Bullet => self.bullet,
+            DamageKind::Saw => self.saw,
+            DamageKind::Impact => self.impact,
+            DamageKind::Explosion => self.explosion,
+            DamageKind::Fall => self.fall,
+        }
+    }
+
+    fn multiplier_mut(&mut self, kind: DamageKind) -> &mut f32 {
+        match kind {
+            DamageKind::Bullet => &mut self.bullet,
+            DamageKind::Saw => &mut self.saw,
+            DamageKind::Impact => &mut self.impact,
+            DamageKind::Explosion => &mut self.explosion,
+            DamageKind::Fall => &mut self.fall,
+        }
+    }
+}
+
+/// Chance for a hit to be critical, multiplying its damage
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+pub struct Critical {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+impl Critical {
+    pub const NONE: Self = Self {
+        chance: 0.0,
+        multiplier: 1.0,
+    };
+
+    pub fn new(chance: f32, multiplier: f32) -> Self {
+        Self { chance, multiplier }
+    }
+
+    /// rolls for a critical hit, returns the resulting damage and if it was critical
+    pub fn roll(&self, damage: u32) -> (u32, bool) {
+        if rand::random::<f32>() < self.chance {
+            ((damage as f32 * self.multiplier).round() as u32, true)
+        } else {
+            (damage, false)
+        }
+    }
+}
+
+/// Flat reduction of every incoming hit, applied after [`Resistances`]
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Armor(pub u32);
+
+/// Scales the damage by the resistance and removes the armor. Fractions are rounded randomly
+/// so that e.g. 1 damage against a 0.25 resistance still hits every fourth time.
+fn mitigate(
+    value: u32,
+    kind: DamageKind,
+    resistances: Option<&Resistances>,
+    armor: Option<&Armor>,
+) -> u32 {
+    let scaled = value as f32 * resistances.map_or(1.0, |r| r.multiplier(kind)).max(0.0);
+    round_randomly(scaled).saturating_sub(armor.map_or(0, |a| a.0))
+}
+
+/// Rounds up with a chance of the fraction, so 0.3 damage hits about every third time
+pub fn round_randomly(value: f32) -> u32 {
+    let mut rounded = value.floor() as u32;
+    if rand::random::<f32>() < value.fract() {
+        rounded += 1;
+    }
+    rounded
+}
 
 fn damage_health(
     mut commands: Commands,
     mut event_reader: EventReader<DamageEvent>,
-    mut health_query: Query<&mut Health>,
+    mut dealt_writer: EventWriter<DamageDealt>,
+    mut health_query: Query<(
+        &mut Health,
+        Option<&Resistances>,
+        Option<&Armor>,
+        Has<Shielded>,
+    )>,
     tractor: Single<Entity, With<Tractor>>,
     assets: Res<HealthAssets>,
 ) {
     for event in event_reader.read() {
-        if let Ok(mut health) = health_query.get_mut(event.entity) {
-            if health.current <= event.value {
-                commands.trigger_targets(Death, event.entity);
+        if let Ok((mut health, resistances, armor, shielded)) = health_query.get_mut(event.entity) {
+            if health.current == 0 || shielded {
+                // already dead, waiting to be despawned, or nothing can hurt it
+                continue;
+            }
+
+            let value = mitigate(event.value, event.kind, resistances, armor);
+            if value == 0 {
+                continue;
+            }
+
+            if health.current <= value {
+                health.current = 0;
+                commands.trigger_targets(
+                    Death {
+                        source: event.source,
+                        kind: event.kind,
+                    },
+                    event.entity,
+                );
             } else {
-                health.current -= event.value;
+                health.current -= value;
             }
 
+            dealt_writer.write(DamageDealt {
+                value,
+                entity: event.entity,
+                source: event.source,
+                kind: event.kind,
+                crit: event.crit,
+            });
+
             if event.entity == *tractor {
                 commands.spawn(sound_effect(assets.tractor_damage_sound.clone()));
             }
@@ -100,14 +303,62 @@ fn damage_health(
     }
 }
 
+/// Returns the impulse magnitude of the collision and how much of it was the tractor ramming,
+/// `other_mass` is `None` for static bodies
+fn impact(
+    tractor_pos: Vec3,
+    tractor_velocity: Vec3,
+    tractor_mass: f32,
+    other_pos: Vec3,
+    other_velocity: Vec3,
+    other_mass: Option<f32>,
+) -> (f32, f32) {
+    let normal = (other_pos - tractor_pos).normalize_or_zero();
+    let closing_speed = (tractor_velocity - other_velocity).dot(normal).max(0.0);
+
+    let reduced_mass = match other_mass {
+        Some(other_mass) => tractor_mass * other_mass / (tractor_mass + other_mass),
+        None => tractor_mass,
+    };
+
+    let ram = ((tractor_velocity.dot(normal) - RAM_MIN_SPEED) / (RAM_FULL_SPEED - RAM_MIN_SPEED))
+        .clamp(0.0, 1.0);
+
+    (reduced_mass * closing_speed, ram)
+}
+
 fn damage_tractor(
     mut commands: Commands,
     mut collision_event_reader: EventReader<CollisionStarted>,
-    tractor: Single<(Entity, &LeftWheels, &RightWheels), With<Tractor>>,
-    apples: Query<(Entity, &AppleStrength), With<Apple>>,
+    tractor: Single<
+        (
+            Entity,
+            &LeftWheels,
+            &RightWheels,
+            &Transform,
+            &LinearVelocity,
+            &ComputedMass,
+        ),
+        With<Tractor>,
+    >,
+    apples: Query<
+        (
+            &AppleStrength,
+            &AppleKind,
+            &Transform,
+            &LinearVelocity,
+            &ComputedMass,
+        ),
+        (With<Apple>, Without<Tractor>),
+    >,
+    trees: Query<&Transform, (With<Tree>, Without<Tractor>)>,
+    turrets: Query<(Entity, &GlobalTransform), With<Turret>>,
     mut event_writer: EventWriter<DamageEvent>,
+    mut part_writer: EventWriter<PartDamageEvent>,
+    mut impact_writer: EventWriter<ImpactEvent>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
 ) {
-    let (tractor, left, right) = *tractor;
+    let (tractor, left, right, tractor_t, tractor_v, tractor_mass) = *tractor;
 
     let mut tractor_entities = HashSet::with_capacity(5);
     tractor_entities.insert(tractor);
@@ -115,53 +366,229 @@ fn damage_tractor(
     tractor_entities.extend(right.collection());
 
     for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
-        for (apple_candidate, tractor_candidate) in [(*entity1, *entity2), (*entity2, *entity1)] {
-            if tractor_entities.contains(&tractor_candidate) {
-                if let Ok((apple, apple_strength)) = apples.get(apple_candidate) {
-                    // event_writer.write(DamageEvent {
-                    //     value: 100,
-                    //     entity: apple,
-                    // });
-
-                    if let Ok(mut ec) = commands.get_entity(apple) {
-                        ec.despawn();
-                    }
+        for (other, tractor_candidate) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if !tractor_entities.contains(&tractor_candidate) {
+                continue;
+            }
 
+            if let Ok((apple_strength, apple_kind, apple_t, apple_v, apple_mass)) =
+                apples.get(other)
+            {
+                let (impulse, ram) = impact(
+                    tractor_t.translation,
+                    tractor_v.0,
+                    tractor_mass.value(),
+                    apple_t.translation,
+                    apple_v.0,
+                    Some(apple_mass.value()),
+                );
+
+                let explosion = apple_kind.contact_explosion(apple_t.translation, other);
+
+                if ram > 0.0 && explosion.is_none() {
+                    // the tractor is moving fast enough to plow through the apple
                     event_writer.write(DamageEvent {
-                        value: apple_strength.damage,
-                        entity: tractor,
+                        value: (impulse * RAM_DAMAGE_PER_IMPULSE * ram).round().max(1.) as u32,
+                        entity: other,
+                        source: Some(tractor),
+                        kind: DamageKind::Impact,
+                        crit: false,
                     });
+                } else {
+                    release_apple(&mut commands, other);
+                }
 
-                    break;
+                if let Some(explosion) = explosion {
+                    explosion_writer.write(explosion);
                 }
+
+                let damage = apple_strength.damage as f32 * (1.0 - ram * RAM_SELF_DAMAGE_REDUCTION);
+                event_writer.write(DamageEvent {
+                    value: damage.round() as u32,
+                    entity: tractor,
+                    source: Some(other),
+                    kind: DamageKind::Impact,
+                    crit: false,
+                });
+
+                part_writer.write(PartDamageEvent {
+                    value: damage.round() as u32,
+                    part: hit_part(
+                        tractor,
+                        tractor_candidate,
+                        tractor_t,
+                        apple_t.translation,
+                        &turrets,
+                    ),
+                    vehicle: tractor,
+                });
+
+                impact_writer.write(ImpactEvent {
+                    tractor,
+                    other,
+                    position: apple_t.translation,
+                    impulse,
+                    ram,
+                });
+
+                break;
             }
+
+            if let Ok(tree_t) = trees.get(other) {
+                let (impulse, ram) = impact(
+                    tractor_t.translation,
+                    tractor_v.0,
+                    tractor_mass.value(),
+                    tree_t.translation.with_y(tractor_t.translation.y),
+                    Vec3::ZERO,
+                    None,
+                );
+
+                if ram > 0.0 {
+                    event_writer.write(DamageEvent {
+                        value: (impulse * RAM_DAMAGE_PER_IMPULSE * ram).round().max(1.) as u32,
+                        entity: other,
+                        source: Some(tractor),
+                        kind: DamageKind::Impact,
+                        crit: false,
+                    });
+                }
+
+                impact_writer.write(ImpactEvent {
+                    tractor,
+                    other,
+                    position: tree_t.translation,
+                    impulse,
+                    ram,
+                });
+
+                break;
+            }
+        }
+    }
+}
+
+/// The part of the tractor an apple at `position` hits when it touches `collider`
+///
+/// Wheels take their own hits, hits on top of the body go to the closest turret
+/// and everything else goes to the engine
+fn hit_part(
+    tractor: Entity,
+    collider: Entity,
+    tractor_t: &Transform,
+    position: Vec3,
+    turrets: &Query<(Entity, &GlobalTransform), With<Turret>>,
+) -> Entity {
+    if collider != tractor {
+        return collider;
+    }
+
+    if position.y > tractor_t.translation.y {
+        let closest = turrets
+            .iter()
+            .map(|(turret, turret_t)| (turret, turret_t.translation().distance(position)))
+            .filter(|(_, distance)| *distance < TURRET_HIT_RANGE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((turret, _)) = closest {
+            return turret;
         }
     }
+
+    tractor
+}
+
+fn explode(
+    mut commands: Commands,
+    mut explosions: EventReader<ExplosionEvent>,
+    targets: Query<(Entity, &GlobalTransform), With<Health>>,
+    mut event_writer: EventWriter<DamageEvent>,
+) {
+    for explosion in explosions.read() {
+        for (target, target_t) in targets.iter() {
+            if Some(target) == explosion.source
+                || target_t.translation().distance(explosion.position) > explosion.radius
+            {
+                continue;
+            }
+
+            event_writer.write(DamageEvent {
+                value: explosion.damage,
+                entity: target,
+                source: explosion.source,
+                kind: DamageKind::Explosion,
+                crit: false,
+            });
+        }
+
+        commands.spawn((
+            apple_death_particles(),
+            Transform::from_translation(explosion.position),
+        ));
+    }
+}
+
+fn play_impact_sounds(
+    mut commands: Commands,
+    mut impacts: EventReader<ImpactEvent>,
+    assets: Res<HealthAssets>,
+) {
+    for impact in impacts.read() {
+        if impact.ram <= 0.0 {
+            // the tractor got hit, the damage sound covers it
+            continue;
+        }
+
+        let volume = (impact.impulse / IMPACT_SOUND_FULL_IMPULSE).clamp(0.1, 1.0);
+        commands.spawn(sound_effect_with(
+            assets.tractor_damage_sound.clone(),
+            volume,
+            0.6,
+        ));
+    }
 }
 
 #[cfg_attr(feature = "dev_native", hot)]
-fn bullet_apple_collision_damage(
+fn bullet_collision_damage(
     mut commands: Commands,
     mut collision_event_reader: EventReader<CollisionStarted>,
-    bullets: Query<(Entity, &Bullet)>,
-    apples: Query<(Entity, &Transform, &Apple), With<Apple>>,
+    bullets: Query<(Entity, &Bullet, Option<&ProjectileOwner>)>,
+    targets: Query<Entity, (With<Health>, Without<Tractor>)>,
+    apples: Query<(&Transform, &Apple)>,
     mut event_writer: EventWriter<DamageEvent>,
     mut bullet_split: EventWriter<BulletSplitEvent>,
 ) {
+    // a bullet can touch several things in the same frame, but only hits the first
+    let mut spent_bullets = HashSet::new();
+
     for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
-        for (apple_candidate, bullet_candidate) in [(*entity1, *entity2), (*entity2, *entity1)] {
-            if let (Ok((apple_entity, apple_t, apple)), Ok((bullet_e, bullet))) =
-                (apples.get(apple_candidate), bullets.get(bullet_candidate))
-            {
-                event_writer.write(DamageEvent {
-                    value: bullet.damage,
-                    entity: apple_entity,
-                });
+        for (target_candidate, bullet_candidate) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            let (Ok(target), Ok((bullet_e, bullet, owner))) =
+                (targets.get(target_candidate), bullets.get(bullet_candidate))
+            else {
+                continue;
+            };
 
-                if let Ok(mut ec) = commands.get_entity(bullet_e) {
-                    ec.despawn();
-                }
+            if !spent_bullets.insert(bullet_e) {
+                break;
+            }
+
+            let owner = owner.map(|owner| owner.0);
+
+            let (value, crit) = bullet.critical.roll(bullet.damage);
+            event_writer.write(DamageEvent {
+                value,
+                entity: target,
+                source: Some(owner.unwrap_or(bullet_e)),
+                kind: DamageKind::Bullet,
+                crit,
+            });
+
+            if let Ok(mut ec) = commands.get_entity(bullet_e) {
+                ec.despawn();
+            }
 
+            // only apples burst into more bullets
+            if let Ok((apple_t, apple)) = apples.get(target) {
                 let percent: f32 = rand::random();
                 if percent < bullet.split_probability {
                     // info!("splitting bullet!");
@@ -169,13 +596,16 @@ fn bullet_apple_collision_damage(
                     if bullet.damage > 0 {
                         bullet_split.write(BulletSplitEvent {
                             center: apple_t.translation,
-                            radius: apple.radius * apple_t.scale.x,
+                            // the radius is already scaled with the apple
+                            radius: apple.radius,
                             bullet,
+                            source: target,
+                            owner,
                         });
                     }
                 }
-                break; // Only need to damage once per collision
             }
+            break; // Only need to damage once per collision
         }
     }
 }
@@ -23,6 +23,10 @@ pub fn plugin(app: &mut App) {
 }
 
 pub fn healthbar(start_percent: f32) -> impl Bundle {
+    healthbar_with_color(start_percent, GREEN_300.into())
+}
+
+pub fn healthbar_with_color(start_percent: f32, color: Color) -> impl Bundle {
     (
         Name::new("UnitHealthBar"),
         Node {
@@ -46,7 +50,7 @@ pub fn healthbar(start_percent: f32) -> impl Bundle {
                 height: Val::Percent(100.),
                 ..Default::default()
             },
-            BackgroundColor(GREEN_300.into()),
+            BackgroundColor(color),
         )),
     )
 }
@@ -4,12 +4,18 @@ use crate::theme::palette::{BUTTON_TEXT, LABEL_TEXT};
 use crate::{
     PausableSystems, ReplaceOnHotreload,
     gameplay::{
+        ability::AbilitySlot,
         apple::Apple,
+        flip::Overturned,
+        fuel::Fuel,
         health::Health,
+        lives::Lives,
+        saw::SawHeat,
         score::{Currency, ScoreCounter},
-        tractor::{Tractor, TractorSaw},
+        tractor::{SawMount, Tractor, TractorAssets, TractorSaw, spawn_tractor_saw},
         tree::Tree,
-        turret::TurretDamage,
+        turret::{TurretDamage, TurretMagazine},
+        vehicle_kind::VehicleKind,
     },
     theme::widget,
 };
@@ -19,15 +25,30 @@ use bevy::{color::palettes::tailwind::*, ecs::system::IntoObserverSystem, input:
 use bevy_tweening::*;
 use bevy_tweening::{Animator, Tween, lens::TransformPositionLens};
 use core::fmt;
+use std::time::Duration;
 
 const HUD_WIDTH_ELEMENT: f32 = 82.0;
 
+const SAW_WIDTH_STEP: f32 = 0.25;
+const SAW_MAX_WIDTH: f32 = 2.0;
+const SAW_RATE_OF_FIRE_FACTOR: f32 = 0.8;
+const SAW_MIN_RATE_OF_FIRE_MS: u64 = 100;
+const SAW_COOLING_STEP: f32 = 0.05;
+const SAW_MAX_COOLING: f32 = 0.5;
+const SAW_MOUNT_COST: u32 = 3;
+const MAGAZINE_SIZE_STEP: u32 = 4;
+const RELOAD_TIME_FACTOR: f32 = 0.8;
+const MIN_RELOAD_TIME_MS: u64 = 400;
+
 #[derive(Component, Default)]
 pub struct PointCounter;
 
 #[derive(Component, Default)]
 pub struct AppleCounter;
 
+#[derive(Component, Default)]
+struct LivesCounter;
+
 #[derive(Component, Default)]
 pub struct TreeCounter;
 
@@ -38,16 +59,57 @@ struct Healthbar;
 struct TurretUpdateEvent;
 #[derive(Event, Default)]
 struct SawUpdateEvent;
+#[derive(Event, Default)]
+struct SawWidthUpdateEvent;
+#[derive(Event, Default)]
+struct SawRateUpdateEvent;
+#[derive(Event, Default)]
+struct SawCoolingUpdateEvent;
+#[derive(Event, Default)]
+struct SawMountUpdateEvent;
+#[derive(Event, Default)]
+struct MagazineUpdateEvent;
+#[derive(Event, Default)]
+struct ReloadUpdateEvent;
+
+#[derive(Component, Default)]
+struct SawHeatbar;
+
+#[derive(Component, Default)]
+struct FuelGauge;
+
+#[derive(Component, Default)]
+struct FuelGaugeFrame;
+
+#[derive(Component, Default)]
+struct AbilityCooldownbar;
+
+#[derive(Component, Default)]
+struct AbilityLabel;
+
+#[derive(Component, Default)]
+struct FlipHint;
 
 pub fn hud_plugin(app: &mut App) {
     app.add_event::<SawUpdateEvent>();
+    app.add_event::<SawWidthUpdateEvent>();
+    app.add_event::<SawRateUpdateEvent>();
+    app.add_event::<SawCoolingUpdateEvent>();
+    app.add_event::<SawMountUpdateEvent>();
     app.add_event::<TurretUpdateEvent>();
+    app.add_event::<MagazineUpdateEvent>();
+    app.add_event::<ReloadUpdateEvent>();
 
     app.add_systems(
         Update,
         (
             update_points,
+            update_lives,
             update_healthbar,
+            update_fuel_gauge,
+            update_saw_heatbar,
+            update_ability_indicator,
+            update_flip_hint,
             update_apple_counter,
             update_tree_counter,
             update_upgrade_counter,
@@ -60,7 +122,13 @@ pub fn hud_plugin(app: &mut App) {
         Update,
         (
             upgrade_saw.run_if(on_event::<SawUpdateEvent>),
+            upgrade_saw_width.run_if(on_event::<SawWidthUpdateEvent>),
+            upgrade_saw_rate.run_if(on_event::<SawRateUpdateEvent>),
+            upgrade_saw_cooling.run_if(on_event::<SawCoolingUpdateEvent>),
+            upgrade_saw_mount.run_if(on_event::<SawMountUpdateEvent>),
             upgrade_turret.run_if(on_event::<TurretUpdateEvent>),
+            upgrade_magazine.run_if(on_event::<MagazineUpdateEvent>),
+            upgrade_reload.run_if(on_event::<ReloadUpdateEvent>),
             toggle_upgrade_indicators,
         )
             .in_set(PausableSystems),
@@ -69,15 +137,48 @@ pub fn hud_plugin(app: &mut App) {
 
 fn keybind_updates(
     keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
     mut turrw: EventWriter<TurretUpdateEvent>,
     mut saww: EventWriter<SawUpdateEvent>,
+    mut saw_width: EventWriter<SawWidthUpdateEvent>,
+    mut saw_rate: EventWriter<SawRateUpdateEvent>,
+    mut saw_cooling: EventWriter<SawCoolingUpdateEvent>,
+    mut saw_mount: EventWriter<SawMountUpdateEvent>,
+    mut magazine: EventWriter<MagazineUpdateEvent>,
+    mut reload: EventWriter<ReloadUpdateEvent>,
 ) {
-    if keyboard.just_pressed(KeyCode::Digit1) {
+    // face buttons pick the first four upgrades, or the last four while LB is held
+    let shifted = gamepads
+        .iter()
+        .any(|gamepad| gamepad.pressed(GamepadButton::LeftTrigger));
+    let face = |button: GamepadButton, shift: bool| {
+        shift == shifted && gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+    };
+
+    if keyboard.just_pressed(KeyCode::Digit1) || face(GamepadButton::South, false) {
         turrw.write(TurretUpdateEvent);
     }
-    if keyboard.just_pressed(KeyCode::Digit2) {
+    if keyboard.just_pressed(KeyCode::Digit2) || face(GamepadButton::East, false) {
         saww.write(SawUpdateEvent);
     }
+    if keyboard.just_pressed(KeyCode::Digit3) || face(GamepadButton::West, false) {
+        saw_width.write(SawWidthUpdateEvent);
+    }
+    if keyboard.just_pressed(KeyCode::Digit4) || face(GamepadButton::North, false) {
+        saw_rate.write(SawRateUpdateEvent);
+    }
+    if keyboard.just_pressed(KeyCode::Digit5) || face(GamepadButton::South, true) {
+        saw_cooling.write(SawCoolingUpdateEvent);
+    }
+    if keyboard.just_pressed(KeyCode::Digit6) || face(GamepadButton::East, true) {
+        saw_mount.write(SawMountUpdateEvent);
+    }
+    if keyboard.just_pressed(KeyCode::Digit7) || face(GamepadButton::West, true) {
+        magazine.write(MagazineUpdateEvent);
+    }
+    if keyboard.just_pressed(KeyCode::Digit8) || face(GamepadButton::North, true) {
+        reload.write(ReloadUpdateEvent);
+    }
 }
 
 fn update_tree_counter(tree: Query<&Tree>, mut counter: Single<&mut Text, With<TreeCounter>>) {
@@ -92,6 +193,87 @@ fn update_points(score: Res<ScoreCounter>, mut hud_score: Single<&mut Text, With
     hud_score.0 = format!("{}", score.points);
 }
 
+fn update_lives(lives: Res<Lives>, mut counter: Single<&mut Text, With<LivesCounter>>) {
+    counter.0 = format!("{}", lives.0);
+}
+
+fn update_saw_heatbar(
+    saws: Query<&SawHeat>,
+    mut heatbar: Single<(&mut Node, &mut BackgroundColor), With<SawHeatbar>>,
+) {
+    let (node, color) = &mut *heatbar;
+
+    let heat = saws.iter().map(|heat| heat.heat).fold(0.0, f32::max);
+    node.width = Val::Percent(heat * 100.);
+
+    if saws.iter().any(|heat| heat.overheated) {
+        color.0 = RED_600.into();
+    } else {
+        color.0 = ORANGE_400.into();
+    }
+}
+
+fn update_fuel_gauge(
+    tractor: Query<&Fuel, With<Tractor>>,
+    mut gauge: Single<(&mut Node, &mut BackgroundColor), With<FuelGauge>>,
+    mut frame: Single<&mut Visibility, With<FuelGaugeFrame>>,
+) {
+    let (node, color) = &mut *gauge;
+
+    // runs without fuel have no use for the gauge
+    let Ok(fuel) = tractor.single() else {
+        frame.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    frame.set_if_neq(Visibility::Inherited);
+
+    node.width = Val::Percent(fuel.fraction() * 100.);
+    color.0 = if fuel.fraction() < 0.2 {
+        RED_600.into()
+    } else {
+        AMBER_400.into()
+    };
+}
+
+/// Fills up while the ability cools down and lights up once it's ready
+fn update_ability_indicator(
+    tractor: Query<&AbilitySlot, With<Tractor>>,
+    mut bar: Single<(&mut Node, &mut BackgroundColor), With<AbilityCooldownbar>>,
+    mut label: Single<&mut Text, With<AbilityLabel>>,
+) {
+    let (node, color) = &mut *bar;
+
+    let Ok(slot) = tractor.single() else {
+        node.width = Val::Percent(0.);
+        return;
+    };
+
+    node.width = Val::Percent(slot.readiness() * 100.);
+    if slot.cooldown.finished() {
+        color.0 = SKY_400.into();
+        label.0 = format!("{} ready", slot.ability.name());
+    } else {
+        color.0 = GRAY_500.into();
+        label.0 = slot.ability.name().to_string();
+    }
+}
+
+fn update_flip_hint(
+    tractor: Query<Option<&Overturned>, With<Tractor>>,
+    mut hint: Single<(&mut Visibility, &mut Text), With<FlipHint>>,
+) {
+    let (visibility, text) = &mut *hint;
+
+    let Ok(Some(overturned)) = tractor.single() else {
+        **visibility = Visibility::Hidden;
+        return;
+    };
+
+    **visibility = Visibility::Inherited;
+    let remaining = overturned.auto_right.remaining_secs().ceil();
+    text.0 = format!("Overturned! Press F or Select to flip back ({remaining}s)");
+}
+
 fn update_healthbar(
     tractor: Query<&Health, With<Tractor>>,
     mut healthbar: Single<&mut Node, With<Healthbar>>,
@@ -133,6 +315,13 @@ pub fn spawn_hud(commands: &mut Commands) {
                 justify_content: JustifyContent::Center,
                 ..default()
             },),
+            create_score_hud::<LivesCounter>("Lives"),
+            (Node {
+                height: Px(10.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },),
             // upgrade_tracker::<AppleCounter>("Apples Alive"),
             // (Node {
             //     height: Px(10.0),
@@ -152,6 +341,10 @@ pub fn spawn_hud(commands: &mut Commands) {
     // commands.spawn(stat_tracker());
     // commands.spawn(upgrade_tracker());
     commands.spawn(healthbar());
+    commands.spawn(fuel_gauge());
+    commands.spawn(saw_heatbar());
+    commands.spawn(ability_indicator());
+    commands.spawn(flip_hint());
     commands.spawn(update_hud());
 }
 
@@ -162,6 +355,18 @@ struct TurretUpdateIndicator;
 
 #[derive(Component, Default)]
 struct SawUpdateCounter;
+#[derive(Component, Default)]
+struct SawWidthUpdateCounter;
+#[derive(Component, Default)]
+struct SawRateUpdateCounter;
+#[derive(Component, Default)]
+struct SawCoolingUpdateCounter;
+#[derive(Component, Default)]
+struct SawMountUpdateCounter;
+#[derive(Component, Default)]
+struct MagazineUpdateCounter;
+#[derive(Component, Default)]
+struct ReloadUpdateCounter;
 
 fn update_hud() -> impl Bundle {
     (
@@ -174,17 +379,27 @@ fn update_hud() -> impl Bundle {
             padding: UiRect::all(Val::Px(4.)),
             position_type: PositionType::Absolute,
             flex_direction: FlexDirection::Column,
+            row_gap: Px(10.0),
             ..Default::default()
         },
         children![
             create_upgrade_hud::<TurretUpdateEvent, TurretUpdateCounter>("Turret dmg", "Press 1"),
-            (Node {
-                height: Px(10.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },),
             create_upgrade_hud::<SawUpdateEvent, SawUpdateCounter>("Saw dmg", "Press 2"),
+            create_upgrade_hud::<SawWidthUpdateEvent, SawWidthUpdateCounter>(
+                "Saw width",
+                "Press 3"
+            ),
+            create_upgrade_hud::<SawRateUpdateEvent, SawRateUpdateCounter>("Saw speed", "Press 4"),
+            create_upgrade_hud::<SawCoolingUpdateEvent, SawCoolingUpdateCounter>(
+                "Saw cooling",
+                "Press 5"
+            ),
+            create_upgrade_hud::<SawMountUpdateEvent, SawMountUpdateCounter>(
+                "Extra saw",
+                "Press 6"
+            ),
+            create_upgrade_hud::<MagazineUpdateEvent, MagazineUpdateCounter>("Magazine", "Press 7"),
+            create_upgrade_hud::<ReloadUpdateEvent, ReloadUpdateCounter>("Reload", "Press 8"),
             // upgrades_counter(),
             // update_button::<TurretUpdateEvent, TurretUpdateCounter>("Turret", "Press 1"),
             // update_button::<SawUpdateEvent, SawUpdateCounter>("Saw", "Press 2"),
@@ -252,6 +467,155 @@ fn upgrade_saw(
     }
 }
 
+fn upgrade_saw_width(
+    mut upd_counters: Query<&mut Text, With<SawWidthUpdateCounter>>,
+    mut saws: Query<(&mut TractorSaw, &SawMount, &mut Transform)>,
+    mut currency: ResMut<Currency>,
+) {
+    if saws.iter().all(|(saw, _, _)| saw.width >= SAW_MAX_WIDTH) {
+        return;
+    }
+
+    if currency.spend(1) {
+        for (mut saw, mount, mut transform) in saws.iter_mut() {
+            saw.width = (saw.width + SAW_WIDTH_STEP).min(SAW_MAX_WIDTH);
+            transform.scale = mount.scale(saw.width);
+            for mut upd_counter in upd_counters.iter_mut() {
+                *upd_counter = Text::new(format!("x{:.2}", saw.width));
+            }
+        }
+    }
+}
+
+fn upgrade_saw_rate(
+    mut upd_counters: Query<&mut Text, With<SawRateUpdateCounter>>,
+    mut saws: Query<&mut TractorSaw>,
+    mut currency: ResMut<Currency>,
+) {
+    let min_rate = Duration::from_millis(SAW_MIN_RATE_OF_FIRE_MS);
+    if saws.iter().all(|saw| saw.rate_of_fire <= min_rate) {
+        return;
+    }
+
+    if currency.spend(1) {
+        for mut saw in saws.iter_mut() {
+            saw.rate_of_fire = saw
+                .rate_of_fire
+                .mul_f32(SAW_RATE_OF_FIRE_FACTOR)
+                .max(min_rate);
+            for mut upd_counter in upd_counters.iter_mut() {
+                *upd_counter = Text::new(format!("{:.1}/s", 1. / saw.rate_of_fire.as_secs_f32()));
+            }
+        }
+    }
+}
+
+fn upgrade_saw_cooling(
+    mut upd_counters: Query<&mut Text, With<SawCoolingUpdateCounter>>,
+    mut saws: Query<&mut SawHeat>,
+    mut currency: ResMut<Currency>,
+) {
+    // also true without any saws, there is nothing to upgrade then
+    if saws
+        .iter()
+        .all(|heat| heat.cooling_per_sec >= SAW_MAX_COOLING)
+    {
+        return;
+    }
+
+    if currency.spend(1) {
+        for mut heat in saws.iter_mut() {
+            heat.cooling_per_sec = (heat.cooling_per_sec + SAW_COOLING_STEP).min(SAW_MAX_COOLING);
+            for mut upd_counter in upd_counters.iter_mut() {
+                *upd_counter = Text::new(format!("{:.2}", heat.cooling_per_sec));
+            }
+        }
+    }
+}
+
+fn upgrade_saw_mount(
+    mut commands: Commands,
+    mut upd_counters: Query<&mut Text, With<SawMountUpdateCounter>>,
+    saws: Query<(&SawMount, &TractorSaw, &SawHeat)>,
+    tractor: Single<(Entity, &VehicleKind), With<Tractor>>,
+    assets: Res<TractorAssets>,
+    mut currency: ResMut<Currency>,
+) {
+    let (tractor, kind) = *tractor;
+    let Some(mount) = SawMount::EXTRA_MOUNTS
+        .into_iter()
+        .find(|mount| !saws.iter().any(|(m, _, _)| m == mount))
+    else {
+        return;
+    };
+
+    // new saws come with the same upgrades as the ones already mounted
+    let (saw, heat) = saws
+        .iter()
+        .next()
+        .map(|(_, saw, heat)| (saw.clone(), heat.clone()))
+        .unwrap_or_default();
+
+    if currency.spend(SAW_MOUNT_COST) {
+        spawn_tractor_saw(
+            &assets,
+            kind.spec(),
+            tractor,
+            &mut commands,
+            mount,
+            saw,
+            heat,
+        );
+        for mut upd_counter in upd_counters.iter_mut() {
+            *upd_counter = Text::new(format!("{}", saws.iter().len() + 1));
+        }
+    }
+}
+
+fn upgrade_magazine(
+    mut upd_counters: Query<&mut Text, With<MagazineUpdateCounter>>,
+    mut magazines: Query<&mut TurretMagazine>,
+    mut currency: ResMut<Currency>,
+) {
+    if currency.spend(1) {
+        for mut magazine in magazines.iter_mut() {
+            magazine.capacity += MAGAZINE_SIZE_STEP;
+            magazine.rounds += MAGAZINE_SIZE_STEP;
+            for mut upd_counter in upd_counters.iter_mut() {
+                *upd_counter = Text::new(format!("{}", magazine.capacity));
+            }
+        }
+    }
+}
+
+fn upgrade_reload(
+    mut upd_counters: Query<&mut Text, With<ReloadUpdateCounter>>,
+    mut magazines: Query<&mut TurretMagazine>,
+    mut currency: ResMut<Currency>,
+) {
+    let min_reload = Duration::from_millis(MIN_RELOAD_TIME_MS);
+    if magazines
+        .iter()
+        .all(|magazine| magazine.reload.duration() <= min_reload)
+    {
+        return;
+    }
+
+    if currency.spend(1) {
+        for mut magazine in magazines.iter_mut() {
+            let reload_time = magazine
+                .reload
+                .duration()
+                .mul_f32(RELOAD_TIME_FACTOR)
+                .max(min_reload);
+            magazine.reload.set_duration(reload_time);
+            for mut upd_counter in upd_counters.iter_mut() {
+                *upd_counter = Text::new(format!("{:.1}s", reload_time.as_secs_f32()));
+            }
+        }
+    }
+}
+
 fn stat_tracker() -> impl Bundle {
     (
         ReplaceOnHotreload,
@@ -446,6 +810,64 @@ fn healthbar() -> impl Bundle {
     )
 }
 
+/// Sits to the right of the healthbar
+fn fuel_gauge() -> impl Bundle {
+    (
+        StateScoped(Screen::InGame),
+        ReplaceOnHotreload,
+        Name::new("fuel gauge"),
+        FuelGaugeFrame,
+        Node {
+            top: Val::Percent(3.0),
+            left: Val::Percent(66.),
+            width: Val::Percent(8.),
+            height: Val::Percent(2.),
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        BorderRadius::all(Val::Px(4.)),
+        Outline::new(Val::Px(3.), Val::Px(0.), WHITE_SMOKE.into()),
+        BackgroundColor(GRAY_800.into()),
+        Children::spawn((Spawn((
+            FuelGauge,
+            BackgroundColor(AMBER_400.into()),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..Default::default()
+            },
+        )),)),
+    )
+}
+
+fn saw_heatbar() -> impl Bundle {
+    (
+        StateScoped(Screen::InGame),
+        ReplaceOnHotreload,
+        Name::new("saw heatbar"),
+        Node {
+            top: Val::Percent(6.0),
+            justify_self: JustifySelf::Center,
+            width: Val::Percent(20.),
+            height: Val::Percent(1.),
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        BorderRadius::all(Val::Px(2.)),
+        Outline::new(Val::Px(2.), Val::Px(0.), WHITE_SMOKE.into()),
+        BackgroundColor(GRAY_800.into()),
+        Children::spawn((Spawn((
+            SawHeatbar,
+            BackgroundColor(ORANGE_400.into()),
+            Node {
+                width: Val::Percent(0.),
+                height: Val::Percent(100.),
+                ..Default::default()
+            },
+        )),)),
+    )
+}
+
 fn update_button<E, C>(name: impl Into<String>, upgrade_text: impl Into<String>) -> impl Bundle
 where
     E: Event + Default,
@@ -645,3 +1067,67 @@ where
 {
     evtw.write(E::default());
 }
+
+fn ability_indicator() -> impl Bundle {
+    (
+        StateScoped(Screen::InGame),
+        ReplaceOnHotreload,
+        Name::new("ability indicator"),
+        Node {
+            bottom: Val::Percent(3.0),
+            justify_self: JustifySelf::Center,
+            width: Val::Percent(15.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(4.),
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        children![
+            (
+                AbilityLabel,
+                Text::default(),
+                TextFont::from_font_size(20.0),
+                TextColor(WHITE_SMOKE.into()),
+            ),
+            (
+                Node {
+                    width: Val::Percent(100.),
+                    height: Px(10.),
+                    ..Default::default()
+                },
+                BorderRadius::all(Val::Px(2.)),
+                Outline::new(Val::Px(2.), Val::Px(0.), WHITE_SMOKE.into()),
+                BackgroundColor(GRAY_800.into()),
+                children![(
+                    AbilityCooldownbar,
+                    BackgroundColor(SKY_400.into()),
+                    Node {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..Default::default()
+                    },
+                )],
+            ),
+        ],
+    )
+}
+
+fn flip_hint() -> impl Bundle {
+    (
+        StateScoped(Screen::InGame),
+        ReplaceOnHotreload,
+        Name::new("flip hint"),
+        FlipHint,
+        Visibility::Hidden,
+        Node {
+            top: Val::Percent(40.0),
+            justify_self: JustifySelf::Center,
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        Text::default(),
+        TextFont::from_font_size(28.0),
+        TextColor(WHITE_SMOKE.into()),
+    )
+}
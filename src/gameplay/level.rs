@@ -1,4 +1,5 @@
 use super::*;
+use crate::gameplay::steering::Obstacle;
 use avian3d::prelude::{ColliderConstructor, Friction, RigidBody};
 use bevy::color::palettes::tailwind::{AMBER_800, GREEN_400};
 use bevy::math::Affine2;
@@ -7,6 +8,7 @@ use bevy::render::mesh::VertexAttributeValues;
 use noise::{BasicMulti, NoiseFn, Perlin};
 use rand::prelude::Distribution;
 use rand_chacha::rand_core::SeedableRng;
+use std::sync::LazyLock;
 
 #[derive(Component)]
 pub struct Ground;
@@ -36,6 +38,10 @@ pub const TERRAIN_HEIGHT: f32 = 40.;
 pub const PLANE_X_SIZE: f32 = 400.;
 pub const PLANE_Z_SIZE: f32 = 400.;
 const PLANE_SUB_DIVISION_COUNT: u32 = 20;
+pub const LEVEL_OFFSET: f32 = -2.0;
+
+static TERRAIN_NOISE: LazyLock<BasicMulti<Perlin>> =
+    LazyLock::new(|| BasicMulti::<Perlin>::new(TERRAIN_SEED));
 
 fn create_plane() -> Mesh {
     Mesh::from(
@@ -46,6 +52,11 @@ fn create_plane() -> Mesh {
     )
 }
 
+fn noise_height(noise: &BasicMulti<Perlin>, x: f32, z: f32) -> f32 {
+    // TODO We can modify 300 the change the level
+    noise.get([x as f64 / 300., z as f64 / 300.]) as f32 * TERRAIN_HEIGHT
+}
+
 fn create_terrain(mut terrain: Mesh, seed: u32) -> Mesh {
     // TODO We can modify the noise type
     let noise = BasicMulti::<Perlin>::new(seed);
@@ -54,12 +65,7 @@ fn create_terrain(mut terrain: Mesh, seed: u32) -> Mesh {
         terrain.attribute_mut(Mesh::ATTRIBUTE_POSITION)
     {
         for pos in positions.iter_mut() {
-            pos[1] = noise.get([
-                // TODO We can modify 300 the change the level
-                pos[0] as f64 / 300.,
-                pos[2] as f64 / 300.,
-            ]) as f32
-                * TERRAIN_HEIGHT;
+            pos[1] = noise_height(&noise, pos[0], pos[2]);
         }
 
         let colors: Vec<[f32; 4]> = positions
@@ -93,6 +99,30 @@ fn create_terrain(mut terrain: Mesh, seed: u32) -> Mesh {
     terrain
 }
 
+/// The ground mesh of the level, before it is moved down by the level offset
+pub fn terrain_mesh() -> Mesh {
+    create_terrain(create_plane(), TERRAIN_SEED)
+}
+
+/// Height of the ground at a spot, interpolated between the vertices like the terrain mesh
+pub fn terrain_height(x: f32, z: f32) -> f32 {
+    let cells = (PLANE_SUB_DIVISION_COUNT + 1) as f32;
+    let step = Vec2::new(PLANE_X_SIZE, PLANE_Z_SIZE) / cells;
+    let grid = ((Vec2::new(x, z) + Vec2::new(PLANE_X_SIZE, PLANE_Z_SIZE) / 2.) / step)
+        .clamp(Vec2::ZERO, Vec2::splat(cells));
+    let corner = grid.floor().min(Vec2::splat(cells - 1.));
+    let t = grid - corner;
+
+    let height = |dx: f32, dz: f32| {
+        let at = (corner + Vec2::new(dx, dz)) * step - Vec2::new(PLANE_X_SIZE, PLANE_Z_SIZE) / 2.;
+        noise_height(&TERRAIN_NOISE, at.x, at.y)
+    };
+    let near = height(0., 0.).lerp(height(1., 0.), t.x);
+    let far = height(0., 1.).lerp(height(1., 1.), t.x);
+
+    near.lerp(far, t.y) + LEVEL_OFFSET
+}
+
 pub fn level(
     commands: &mut Commands,
     world_assets: Res<WorldAssets>,
@@ -100,10 +130,8 @@ pub fn level(
     materials: &mut Assets<StandardMaterial>,
     level_assets: &LevelAssets,
 ) {
-    let plane = create_plane();
-    let terrain = create_terrain(plane, TERRAIN_SEED);
+    let terrain = terrain_mesh();
 
-    const LEVEL_OFFSET: f32 = -2.0;
     const EDGE_START: f32 = 140.;
 
     const DETAILS_SEED: u64 = 2;
@@ -180,6 +208,7 @@ pub fn level(
                 Name::new("Rock"),
                 SceneRoot(level_assets.rock.clone()),
                 Collider::sphere(0.8),
+                Obstacle { radius: 0.8 },
                 RigidBody::Static,
                 Transform {
                     translation: position,
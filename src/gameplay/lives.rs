@@ -0,0 +1,163 @@
+use std::iter;
+
+use crate::PausableSystems;
+use crate::gameplay::ability::Shielded;
+use crate::gameplay::apple::{Apple, release_apple};
+use crate::gameplay::health::{Death, Health};
+use crate::gameplay::level::{PLANE_X_SIZE, PLANE_Z_SIZE, terrain_height};
+use crate::gameplay::tractor::{LeftWheels, RightWheels, Tractor};
+use crate::gameplay::trailer::Trailer;
+use crate::gameplay::vehicle::WheelHub;
+use crate::screens::Screen;
+
+use super::*;
+
+pub const DEFAULT_LIVES: u32 = 3;
+pub const MAX_LIVES: u32 = 5;
+/// The tractor drops in from this high above the ground when it respawns
+const RESPAWN_DROP: f32 = 3.0;
+/// Apples this close to the respawn point are cleared away
+const RESPAWN_CLEAR_RADIUS: f32 = 20.0;
+const RESPAWN_SHIELD_SECS: f32 = 3.0;
+/// The tractor has to be this upright for its spot to count as safe
+const SAFE_UP_DOT: f32 = 0.8;
+/// Spots this close to the edge of the map aren't safe, it's easy to fall off again
+const SAFE_EDGE_MARGIN: f32 = 10.0;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<StartingLives>();
+    app.init_resource::<Lives>();
+    app.init_resource::<SafeSpot>();
+
+    app.add_systems(OnEnter(Screen::InGame), reset_lives);
+    app.add_systems(
+        Update,
+        track_safe_spot
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(lose_life);
+}
+
+/// How many lives a run starts with, one plays without respawns
+#[derive(Resource, Debug)]
+pub struct StartingLives(pub u32);
+
+impl Default for StartingLives {
+    fn default() -> Self {
+        Self(DEFAULT_LIVES)
+    }
+}
+
+/// Lives left in this run, including the current one
+#[derive(Resource, Debug, Default)]
+pub struct Lives(pub u32);
+
+/// Triggered on the tractor when it dies without lives left, the run is over
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LastLifeLost;
+
+/// The last place the tractor was standing upright on the map
+#[derive(Resource, Debug, Default)]
+struct SafeSpot(Vec2);
+
+fn reset_lives(
+    starting: Res<StartingLives>,
+    mut lives: ResMut<Lives>,
+    mut safe_spot: ResMut<SafeSpot>,
+) {
+    lives.0 = starting.0;
+    // the tractor starts out in the middle of the map
+    safe_spot.0 = Vec2::ZERO;
+}
+
+fn track_safe_spot(tractor: Query<&Transform, With<Tractor>>, mut safe_spot: ResMut<SafeSpot>) {
+    let Ok(transform) = tractor.single() else {
+        return;
+    };
+
+    let spot = transform.translation.xz();
+    let bounds = Vec2::new(PLANE_X_SIZE, PLANE_Z_SIZE) / 2.0 - SAFE_EDGE_MARGIN;
+    let on_map = spot.abs().cmplt(bounds).all();
+    if on_map && transform.up().dot(Vec3::Y) > SAFE_UP_DOT {
+        safe_spot.0 = spot;
+    }
+}
+
+/// Respawns the tractor at the last safe spot, or ends the run on the last life
+fn lose_life(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    safe_spot: Res<SafeSpot>,
+    mut tractors: Query<(&mut Health, &LeftWheels, &RightWheels), With<Tractor>>,
+    hubs: Query<(Entity, &WheelHub)>,
+    joints: Query<&FixedJoint>,
+    trailers: Query<(Entity, &Trailer)>,
+    mut bodies: Query<(&mut Transform, &mut LinearVelocity, &mut AngularVelocity), Without<Apple>>,
+    apples: Query<(Entity, &Transform), With<Apple>>,
+) {
+    let tractor = trigger.target();
+    let Ok((mut health, left, right)) = tractors.get_mut(tractor) else {
+        return;
+    };
+
+    lives.0 = lives.0.saturating_sub(1);
+    if lives.0 == 0 {
+        commands.trigger_targets(LastLifeLost, tractor);
+        return;
+    }
+
+    let Ok((body_t, _, _)) = bodies.get(tractor) else {
+        return;
+    };
+    let (x, z) = (safe_spot.0.x, safe_spot.0.y);
+    let spawn = Vec3::new(x, terrain_height(x, z) + RESPAWN_DROP, z);
+    // keep the heading, but put it back on its wheels
+    let (yaw, _, _) = body_t.rotation.to_euler(EulerRot::YXZ);
+    let turn = Quat::from_rotation_y(yaw) * body_t.rotation.inverse();
+    let from = body_t.translation;
+
+    // everything joined to the body moves along with it
+    let hubs = hubs
+        .iter()
+        .filter(|(_, hub)| hub.vehicle == tractor)
+        .map(|(hub, _)| hub);
+    // saws and plows are jointed to the body
+    let attached = joints
+        .iter()
+        .filter(|joint| joint.entity1 == tractor)
+        .map(|joint| joint.entity2);
+    let trailers = trailers
+        .iter()
+        .filter(|(_, trailer)| trailer.vehicle == tractor)
+        .map(|(trailer, _)| trailer);
+    let parts = iter::once(tractor)
+        .chain(left.collection().iter().copied())
+        .chain(right.collection().iter().copied())
+        .chain(hubs)
+        .chain(attached)
+        .chain(trailers);
+
+    for part in parts {
+        let Ok((mut transform, mut velocity, mut angular)) = bodies.get_mut(part) else {
+            continue;
+        };
+        transform.translation = spawn + turn * (transform.translation - from);
+        transform.rotation = turn * transform.rotation;
+        velocity.0 = Vec3::ZERO;
+        angular.0 = Vec3::ZERO;
+    }
+
+    for (apple, transform) in apples.iter() {
+        if transform.translation.distance(spawn) < RESPAWN_CLEAR_RADIUS {
+            release_apple(&mut commands, apple);
+        }
+    }
+
+    health.current = health.max;
+    commands
+        .entity(tractor)
+        .insert(Shielded::for_secs(RESPAWN_SHIELD_SECS));
+}
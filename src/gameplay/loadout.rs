@@ -0,0 +1,303 @@
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::gameplay::attachment::PLOW_MASS;
+use crate::gameplay::tractor::{SawMount, WHEEL_HUB_MASS};
+use crate::gameplay::trailer::TRAILER_MASS;
+use crate::gameplay::turret::BODY_RADIE;
+use crate::gameplay::vehicle::{Suspension, VehicleTuning};
+use crate::gameplay::vehicle_kind::{VehicleKind, VehicleSpec};
+use crate::leaderboard::config_dir;
+
+use super::*;
+
+const MAX_NAME_LENGTH: usize = 16;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Loadout>();
+    app.add_systems(Startup, setup_garage_storage);
+}
+
+fn setup_garage_storage(mut commands: Commands) {
+    commands.insert_resource(
+        Persistent::<Garage>::builder()
+            .name("Garage")
+            .format(StorageFormat::Toml)
+            .path(config_dir().join("garage.toml"))
+            .default(Garage::default())
+            .build()
+            .expect("failed to initialize garage"),
+    )
+}
+
+/// The build the next run starts with and the builds the player saved
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Garage {
+    pub current: Loadout,
+    pub saved: Vec<Loadout>,
+}
+
+impl Garage {
+    /// Saves the current build, replacing a saved one with the same name
+    pub fn save_current(&mut self) {
+        let current = self.current.clone();
+        match self
+            .saved
+            .iter_mut()
+            .find(|saved| saved.name == current.name)
+        {
+            Some(saved) => *saved = current,
+            None => self.saved.push(current),
+        }
+    }
+}
+
+/// Everything the tractor is built from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reflect)]
+pub struct Loadout {
+    pub name: String,
+    /// older garage files have no vehicle, they drove the tractor
+    #[serde(default)]
+    pub vehicle: VehicleKind,
+    pub chassis: Chassis,
+    pub wheels: WheelSet,
+    pub turrets: Vec<TurretMount>,
+    pub attachments: Vec<Attachment>,
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Self {
+            name: "Tractor".to_string(),
+            vehicle: VehicleKind::Tractor,
+            chassis: Chassis::Standard,
+            wheels: WheelSet::Offroad,
+            turrets: vec![TurretMount::Cab],
+            attachments: vec![Attachment::Saw],
+        }
+    }
+}
+
+/// What the garage shows about a build
+#[derive(Debug, Clone, Copy)]
+pub struct LoadoutStats {
+    pub mass: f32,
+    pub top_speed: f32,
+    pub health: u32,
+}
+
+impl Loadout {
+    pub fn has(&self, attachment: Attachment) -> bool {
+        self.attachments.contains(&attachment)
+    }
+
+    pub fn toggle_attachment(&mut self, attachment: Attachment) {
+        toggle(&mut self.attachments, attachment);
+    }
+
+    pub fn toggle_turret(&mut self, mount: TurretMount) {
+        toggle(&mut self.turrets, mount);
+    }
+
+    pub fn push_name(&mut self, character: char) {
+        if self.name.chars().count() < MAX_NAME_LENGTH {
+            self.name.push(character);
+        }
+    }
+
+    pub fn tuning(&self) -> VehicleTuning {
+        self.wheels.tuning()
+    }
+
+    pub fn stats(&self) -> LoadoutStats {
+        let spec = self.vehicle.spec();
+        let body = spec.body_size().element_product() * spec.density * self.chassis.density();
+        let wheel = 4. / 3. * PI * spec.wheel_radius.powi(3) + WHEEL_HUB_MASS;
+        let attachments: f32 = self
+            .attachments
+            .iter()
+            .map(|attachment| attachment.mass(spec))
+            .sum();
+        let top_speed = spec.top_speed * self.chassis.speed_factor();
+        let health = spec.health as i32 + self.chassis.health_bonus();
+
+        LoadoutStats {
+            mass: body + wheel * spec.wheel_count() as f32 + attachments,
+            top_speed: top_speed.min(self.tuning().max_wheel_speed),
+            health: health.max(1) as u32,
+        }
+    }
+}
+
+fn toggle<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    match items.iter().position(|other| *other == item) {
+        Some(index) => {
+            items.remove(index);
+        }
+        None => items.push(item),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Chassis {
+    Standard,
+    /// fast but fragile
+    Light,
+    /// slow, heavy and tough
+    Heavy,
+}
+
+impl Chassis {
+    pub const ALL: [Chassis; 3] = [Chassis::Standard, Chassis::Light, Chassis::Heavy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Chassis::Standard => "Standard",
+            Chassis::Light => "Light",
+            Chassis::Heavy => "Heavy",
+        }
+    }
+
+    /// added to the health of the vehicle
+    pub fn health_bonus(self) -> i32 {
+        match self {
+            Chassis::Standard => 0,
+            Chassis::Light => -2,
+            Chassis::Heavy => 3,
+        }
+    }
+
+    /// multiplied with the density of the vehicle body
+    pub fn density(self) -> f32 {
+        match self {
+            Chassis::Standard => 1.0,
+            Chassis::Light => 0.6,
+            Chassis::Heavy => 1.8,
+        }
+    }
+
+    /// multiplied with the top speed of the vehicle
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            Chassis::Standard => 1.0,
+            Chassis::Light => 1.25,
+            Chassis::Heavy => 0.75,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum WheelSet {
+    /// fast and stiff, slides around on grass
+    Road,
+    Offroad,
+    /// grips anything but is slow
+    Spiked,
+}
+
+impl WheelSet {
+    pub const ALL: [WheelSet; 3] = [WheelSet::Road, WheelSet::Offroad, WheelSet::Spiked];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WheelSet::Road => "Road",
+            WheelSet::Offroad => "Offroad",
+            WheelSet::Spiked => "Spiked",
+        }
+    }
+
+    pub fn tuning(self) -> VehicleTuning {
+        match self {
+            WheelSet::Road => VehicleTuning {
+                motor_torque: 5.0,
+                max_wheel_speed: 20.0,
+                traction: 0.6,
+                suspension: Suspension {
+                    stiffness: 200.0,
+                    damping: 24.0,
+                    travel: 0.15,
+                },
+                ..default()
+            },
+            WheelSet::Offroad => VehicleTuning::default(),
+            WheelSet::Spiked => VehicleTuning {
+                motor_torque: 7.0,
+                max_wheel_speed: 11.0,
+                traction: 1.2,
+                suspension: Suspension {
+                    stiffness: 140.0,
+                    damping: 18.0,
+                    travel: 0.3,
+                },
+                ..default()
+            },
+        }
+    }
+}
+
+/// Where a turret sits on the tractor
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum TurretMount {
+    /// on top of the cab
+    Cab,
+    /// on the hood in front of the cab
+    Hood,
+}
+
+impl TurretMount {
+    pub const ALL: [TurretMount; 2] = [TurretMount::Cab, TurretMount::Hood];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TurretMount::Cab => "Cab",
+            TurretMount::Hood => "Hood",
+        }
+    }
+
+    pub fn position(self, spec: &VehicleSpec) -> Vec3 {
+        let top = spec.top() + BODY_RADIE + spec.wheel_radius;
+        match self {
+            TurretMount::Cab => Vec3::new(0.0, top, spec.size.z * 0.1875),
+            TurretMount::Hood => Vec3::new(0.0, top - 0.5, -spec.size.z * 0.275),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Attachment {
+    /// the front saw
+    Saw,
+    /// a blade low in front that shoves apples aside
+    Plow,
+    /// pulls seeds in to crush them
+    Magnet,
+    /// towed behind, carries fruit to the barn
+    Trailer,
+}
+
+impl Attachment {
+    pub const ALL: [Attachment; 4] = [
+        Attachment::Saw,
+        Attachment::Plow,
+        Attachment::Magnet,
+        Attachment::Trailer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Attachment::Saw => "Saw",
+            Attachment::Plow => "Plow",
+            Attachment::Magnet => "Magnet",
+            Attachment::Trailer => "Trailer",
+        }
+    }
+
+    fn mass(self, spec: &VehicleSpec) -> f32 {
+        match self {
+            Attachment::Saw => SawMount::Front.size(spec).element_product(),
+            Attachment::Plow => PLOW_MASS,
+            Attachment::Magnet => 0.0,
+            Attachment::Trailer => TRAILER_MASS,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::iter;
+
+use crate::PausableSystems;
+use crate::gameplay::ability::Shielded;
+use crate::gameplay::health::Death;
+use crate::gameplay::pickup::{PICKUP_LIFETIME_MS, PickedUp, Pickup, PickupAssets, pickup};
+use crate::gameplay::tractor::{LeftWheel, LeftWheels, RightWheel, RightWheels, Wheel};
+use crate::gameplay::tree::Tree;
+use crate::gameplay::vehicle::VehicleTuning;
+use crate::screens::Screen;
+
+use super::*;
+
+/// How well a part still works when it is broken
+const BROKEN_EFFICIENCY: f32 = 0.3;
+/// Hits on the body this close to a turret hit the turret
+pub const TURRET_HIT_RANGE: f32 = 1.5;
+/// Points a repair kit fixes on every part
+const REPAIR_AMOUNT: u32 = 10;
+const TREE_REPAIR_KIT_CHANCE: f32 = 0.15;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Part>();
+    app.register_type::<PartHealth>();
+
+    app.add_event::<PartDamageEvent>();
+    app.add_systems(
+        Update,
+        (damage_parts, degrade_wheels)
+            .chain()
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(repair_parts).add_observer(drop_repair_kit);
+}
+
+/// A piece of the tractor that wears down on its own
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Part {
+    /// loses traction
+    Wheel,
+    /// fires slower
+    Turret,
+    /// does less damage, wears down while sawing
+    Saw,
+    /// the motor pushes less
+    Engine,
+}
+
+impl Part {
+    pub fn max_health(self) -> u32 {
+        match self {
+            Part::Wheel => 6,
+            Part::Turret => 8,
+            Part::Saw => 60,
+            Part::Engine => 10,
+        }
+    }
+}
+
+/// Health of a single part, the tractor only dies from its own [`Health`](super::health::Health)
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct PartHealth {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl PartHealth {
+    pub fn new(part: Part) -> Self {
+        Self {
+            current: part.max_health(),
+            max: part.max_health(),
+        }
+    }
+
+    /// 1.0 as good as new, down to [`BROKEN_EFFICIENCY`] when broken
+    pub fn efficiency(&self) -> f32 {
+        let condition = self.current as f32 / self.max as f32;
+        BROKEN_EFFICIENCY + (1.0 - BROKEN_EFFICIENCY) * condition
+    }
+
+    pub fn repair(&mut self, amount: u32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+/// Everything a part needs
+pub fn part(part: Part) -> impl Bundle {
+    (part, PartHealth::new(part))
+}
+
+/// Wears down a part of a vehicle
+#[derive(Event, Debug)]
+pub struct PartDamageEvent {
+    pub value: u32,
+    pub part: Entity,
+    pub vehicle: Entity,
+}
+
+fn damage_parts(
+    mut events: EventReader<PartDamageEvent>,
+    mut parts: Query<&mut PartHealth>,
+    shielded: Query<(), With<Shielded>>,
+) {
+    for event in events.read() {
+        if shielded.contains(event.vehicle) {
+            continue;
+        }
+
+        if let Ok(mut health) = parts.get_mut(event.part) {
+            health.current = health.current.saturating_sub(event.value);
+        }
+    }
+}
+
+/// Damaged wheels slip
+fn degrade_wheels(
+    mut wheels: Query<
+        (
+            &PartHealth,
+            &mut Friction,
+            Option<&LeftWheel>,
+            Option<&RightWheel>,
+        ),
+        (With<Wheel>, Changed<PartHealth>),
+    >,
+    tunings: Query<&VehicleTuning>,
+) {
+    for (health, mut friction, left, right) in wheels.iter_mut() {
+        let vehicle = left
+            .map(|wheel| wheel.vehicle)
+            .or(right.map(|wheel| wheel.vehicle));
+        let Some(tuning) = vehicle.and_then(|vehicle| tunings.get(vehicle).ok()) else {
+            continue;
+        };
+
+        friction.dynamic_coefficient = tuning.traction * health.efficiency();
+        friction.static_coefficient = tuning.traction * health.efficiency();
+    }
+}
+
+/// Fixes the parts of the vehicle that picked up the kit
+fn repair_parts(
+    trigger: Trigger<PickedUp>,
+    vehicles: Query<(&LeftWheels, &RightWheels, Option<&Children>)>,
+    joints: Query<&FixedJoint>,
+    mut parts: Query<&mut PartHealth>,
+) {
+    let Pickup::Repair(amount) = trigger.0 else {
+        return;
+    };
+
+    let vehicle = trigger.target();
+    let Ok((left, right, children)) = vehicles.get(vehicle) else {
+        return;
+    };
+
+    // turrets sit on the body, saws are jointed to it
+    let turrets = children.into_iter().flatten().copied();
+    let saws = joints
+        .iter()
+        .filter(|joint| joint.entity1 == vehicle)
+        .map(|joint| joint.entity2);
+    let owned = iter::once(vehicle)
+        .chain(left.collection().iter().copied())
+        .chain(right.collection().iter().copied())
+        .chain(turrets)
+        .chain(saws);
+
+    let mut owned_parts = parts.iter_many_mut(owned);
+    while let Some(mut health) = owned_parts.fetch_next() {
+        health.repair(amount);
+    }
+}
+
+/// Felled trees sometimes leave a repair kit behind
+fn drop_repair_kit(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    trees: Query<&Transform, With<Tree>>,
+    assets: Res<PickupAssets>,
+) {
+    let Ok(transform) = trees.get(trigger.target()) else {
+        return;
+    };
+
+    if rand::random::<f32>() < TREE_REPAIR_KIT_CHANCE {
+        // next to where a fuel can would drop
+        let position = transform.translation + Vec3::X * 1.5;
+        commands.spawn((
+            pickup(&assets, Pickup::Repair(REPAIR_AMOUNT), position),
+            DespawnAfter::millis(PICKUP_LIFETIME_MS),
+        ));
+    }
+}
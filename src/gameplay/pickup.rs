@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use crate::PausableSystems;
+use crate::gameplay::tractor::{Tractor, Wheel};
+use crate::screens::Screen;
+use bevy::color::palettes::tailwind::{GREEN_500, ORANGE_500, RED_500};
+
+use super::*;
+
+const PICKUP_SIZE: f32 = 0.8;
+/// Turns per second, so pickups catch the eye
+const PICKUP_SPIN: f32 = 0.5;
+/// How long dropped pickups lie around
+pub const PICKUP_LIFETIME_MS: u64 = 30_000;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<PickupAssets>();
+
+    app.add_systems(
+        Update,
+        (spin_pickups, collect_pickups)
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Something the tractor collects by driving into it
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum Pickup {
+    /// refills this much fuel
+    Fuel(f32),
+    /// fixes this much on every part
+    Repair(u32),
+    /// goes into the trailer, to be sold at the barn
+    Fruit,
+}
+
+/// Triggered on the tractor when it drives into a pickup
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PickedUp(pub Pickup);
+
+#[derive(Resource)]
+pub struct PickupAssets {
+    mesh: Handle<Mesh>,
+    fuel_material: Handle<StandardMaterial>,
+    repair_material: Handle<StandardMaterial>,
+    fruit_material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PickupAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Cuboid::new(
+            PICKUP_SIZE * 0.7,
+            PICKUP_SIZE,
+            PICKUP_SIZE * 0.4,
+        ));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let fuel_material = materials.add(StandardMaterial::from_color(ORANGE_500));
+        let repair_material = materials.add(StandardMaterial::from_color(GREEN_500));
+        let fruit_material = materials.add(StandardMaterial::from_color(RED_500));
+
+        Self {
+            mesh,
+            fuel_material,
+            repair_material,
+            fruit_material,
+        }
+    }
+}
+
+impl PickupAssets {
+    fn material(&self, pickup: Pickup) -> Handle<StandardMaterial> {
+        match pickup {
+            Pickup::Fuel(_) => self.fuel_material.clone(),
+            Pickup::Repair(_) => self.repair_material.clone(),
+            Pickup::Fruit => self.fruit_material.clone(),
+        }
+    }
+}
+
+/// A pickup floating just above the ground at `position`
+pub fn pickup(assets: &PickupAssets, pickup: Pickup, position: Vec3) -> impl Bundle {
+    (
+        Name::new("Pickup"),
+        pickup,
+        StateScoped(Screen::InGame),
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material(pickup)),
+        Transform::from_translation(position + Vec3::Y * PICKUP_SIZE),
+        Sensor,
+        Collider::sphere(PICKUP_SIZE),
+        CollisionEventsEnabled,
+    )
+}
+
+fn spin_pickups(time: Res<Time>, mut pickups: Query<&mut Transform, With<Pickup>>) {
+    for mut transform in pickups.iter_mut() {
+        transform.rotate_y(PICKUP_SPIN * 2.0 * PI * time.delta_secs());
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut collision_event_reader: EventReader<CollisionStarted>,
+    tractor: Single<Entity, With<Tractor>>,
+    tractor_parts: Query<(), Or<(With<Tractor>, With<Wheel>)>>,
+    pickups: Query<&Pickup>,
+) {
+    // the body and a wheel can touch the same pickup at once
+    let mut collected = HashSet::new();
+
+    for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
+        for (tractor_candidate, other) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            let (Ok(()), Ok(pickup)) = (tractor_parts.get(tractor_candidate), pickups.get(other))
+            else {
+                continue;
+            };
+
+            if !collected.insert(other) {
+                break;
+            }
+
+            commands.trigger_targets(PickedUp(*pickup), *tractor);
+            commands.entity(other).despawn();
+        }
+    }
+}
@@ -1,11 +1,54 @@
 use crate::{
+    PausableSystems,
     audio::sound_effect,
-    gameplay::{health::DamageEvent, tractor::TractorSaw},
+    gameplay::{
+        health::{Critical, DamageEvent, DamageKind, round_randomly},
+        parts::PartHealth,
+        tractor::TractorSaw,
+    },
     screens::Screen,
 };
 
 use super::*;
 
+pub const SAW_HEAT_PER_HIT: f32 = 0.08;
+pub const SAW_COOLING_PER_SEC: f32 = 0.2;
+const SAW_OVERHEAT_COOLDOWN_SEC: f32 = 3.0;
+/// heat left in the saw after it has cooled down from overheating
+const SAW_HEAT_AFTER_OVERHEAT: f32 = 0.5;
+
+/// Heat of a saw, 1.0 is overheated, overheated saws do not cut until they have cooled down
+#[derive(Component, Debug, Clone)]
+pub struct SawHeat {
+    pub heat: f32,
+    pub per_hit: f32,
+    pub cooling_per_sec: f32,
+    pub overheated: bool,
+    cooldown: Timer,
+}
+
+impl Default for SawHeat {
+    fn default() -> Self {
+        Self {
+            heat: 0.0,
+            per_hit: SAW_HEAT_PER_HIT,
+            cooling_per_sec: SAW_COOLING_PER_SEC,
+            overheated: false,
+            cooldown: Timer::from_seconds(SAW_OVERHEAT_COOLDOWN_SEC, TimerMode::Once),
+        }
+    }
+}
+
+impl SawHeat {
+    fn add_hit(&mut self) {
+        self.heat = (self.heat + self.per_hit).min(1.0);
+        if self.heat >= 1.0 {
+            self.overheated = true;
+            self.cooldown.reset();
+        }
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct Sawable {
     timer: Timer,
@@ -35,29 +78,75 @@ impl FromWorld for SawAssets {
 
 fn check_saw_colitions(
     collisions: Collisions,
-    sawables: Query<(Entity, &mut Sawable)>,
-    saw: Single<(Entity, &TractorSaw, &GlobalTransform)>,
+    mut sawables: Query<(Entity, &mut Sawable)>,
+    saws: Query<(
+        Entity,
+        &TractorSaw,
+        &mut SawHeat,
+        &Critical,
+        &GlobalTransform,
+        Option<&mut PartHealth>,
+    )>,
     mut commands: Commands,
     assets: Res<SawAssets>,
 ) {
-    let (saw_entity, saw, saw_gt) = saw.into_inner();
-
-    for (sawable_entity, mut sawable) in sawables {
-        if collisions.contains(sawable_entity, saw_entity) && sawable.timer.finished() {
-            // Object is currently beeing damaged by the saw
-            commands.send_event(DamageEvent {
-                value: saw.damage,
-                entity: sawable_entity,
-            });
+    for (saw_entity, saw, mut heat, critical, saw_gt, mut condition) in saws {
+        if heat.overheated {
+            continue;
+        }
+
+        for (sawable_entity, mut sawable) in sawables.iter_mut() {
+            if !collisions.contains(sawable_entity, saw_entity) || !sawable.timer.finished() {
+                continue;
+            }
+
+            // Object is currently beeing damaged by the saw, dull saws do less damage
+            let efficiency = condition
+                .as_ref()
+                .map_or(1.0, |condition| condition.efficiency());
+            let damage = round_randomly(saw.damage as f32 * efficiency);
+            if damage > 0 {
+                let (value, crit) = critical.roll(damage);
+                commands.send_event(DamageEvent {
+                    value,
+                    entity: sawable_entity,
+                    source: Some(saw_entity),
+                    kind: DamageKind::Saw,
+                    crit,
+                });
+            }
             // Update rate of fire
             sawable.timer.set_duration(saw.rate_of_fire);
             sawable.timer.reset();
 
+            if let Some(condition) = condition.as_mut() {
+                condition.current = condition.current.saturating_sub(1);
+            }
+
             commands.spawn(sound_effect(assets.damage_sound.clone()));
             commands.spawn((
                 sawdust_particles(),
                 Transform::from_translation(saw_gt.translation()),
             ));
+
+            heat.add_hit();
+            if heat.overheated {
+                break;
+            }
+        }
+    }
+}
+
+fn cool_saws(mut saws: Query<&mut SawHeat>, time: Res<Time>) {
+    for mut heat in saws.iter_mut() {
+        if heat.overheated {
+            heat.cooldown.tick(time.delta());
+            if heat.cooldown.finished() {
+                heat.overheated = false;
+                heat.heat = SAW_HEAT_AFTER_OVERHEAT;
+            }
+        } else {
+            heat.heat = (heat.heat - heat.cooling_per_sec * time.delta_secs()).max(0.0);
         }
     }
 }
@@ -118,6 +207,9 @@ pub fn plugin(app: &mut App) {
         (
             check_saw_colitions.run_if(in_state(Screen::InGame)),
             check_sawable_timers.run_if(in_state(Screen::InGame)),
+            cool_saws
+                .run_if(in_state(Screen::InGame))
+                .in_set(PausableSystems),
         ),
     );
 }
@@ -3,7 +3,14 @@ use crate::audio::sound_effect;
 use crate::gameplay::tree::Tree;
 use crate::leaderboard::AddUserScore;
 use crate::{
-    gameplay::{apple::Apple, health::Death, tractor::Tractor},
+    gameplay::{
+        apple::Apple,
+        apple_kind::AppleKind,
+        health::{DamageKind, Death},
+        lives::LastLifeLost,
+        tractor::Tractor,
+        vehicle_kind::VehicleKind,
+    },
     screens::Screen,
 };
 
@@ -69,14 +76,21 @@ pub fn plugin(app: &mut App) {
          mut commands: Commands,
          mut score: ResMut<ScoreCounter>,
          mut currency: ResMut<Currency>,
-         apples: Query<&Apple>,
+         apples: Query<&AppleKind, With<Apple>>,
          trees: Query<&Tree>,
-         tractor: Query<&Tractor>,
          assets: Res<ScoreAssets>| {
-            if let Ok(_apple) = apples.get(trigger.target()) {
-                score.points += 1;
-                if score.points % 10 == 0 {
-                    currency.add(1);
+            if let Ok(kind) = apples.get(trigger.target()) {
+                // rammed apples are worth double
+                let points = if trigger.event().kind == DamageKind::Impact {
+                    kind.points() * 2
+                } else {
+                    kind.points()
+                };
+                for _ in 0..points {
+                    score.points += 1;
+                    if score.points % 10 == 0 {
+                        currency.add(1);
+                    }
                 }
                 commands.spawn(sound_effect(assets.sound.clone()));
             }
@@ -90,10 +104,19 @@ pub fn plugin(app: &mut App) {
                 }
                 commands.spawn(sound_effect(assets.sound.clone()));
             }
+        },
+    );
 
-            if let Ok(_tractor) = tractor.get(trigger.target()) {
+    // only the final death ends the run
+    app.add_observer(
+        |trigger: Trigger<LastLifeLost>,
+         mut commands: Commands,
+         score: Res<ScoreCounter>,
+         tractor: Query<&VehicleKind, With<Tractor>>| {
+            if let Ok(vehicle) = tractor.get(trigger.target()) {
                 commands.trigger(AddUserScore {
                     value: score.points as f32,
+                    vehicle: *vehicle,
                 });
             }
         },
@@ -1,28 +1,52 @@
 use crate::{
     PausableSystems, ReplaceOnHotreload,
-    gameplay::{health::Health, level::Ground, tree::TreeSpawnEvent},
+    gameplay::{
+        GameLayer,
+        apple::apple_death_particles,
+        attachment::Magnetic,
+        fertility::Fertility,
+        health::{DamageEvent, DamageKind, Death, Health},
+        healthbars::healthbar,
+        level::Ground,
+        saw::Sawable,
+        tractor::{Tractor, Wheel},
+        tree::TreeSpawnEvent,
+    },
     screens::Screen,
 };
 
 use super::*;
 use avian3d::prelude::{Collider, LinearDamping, LinearVelocity, Mass, RigidBody};
 use bevy::color::palettes::basic::BLACK;
+use bevy::color::palettes::tailwind::{GREEN_500, GREEN_700};
+use bevy_ui_anchor::AnchoredUiNodes;
 use std::time::Duration;
 
 const SEED_RADIUS: f32 = 0.1;
+/// Time a seed lies on the ground before it germinates or rots away
 const SEED_DESPAWN_TIME_SEC: u64 = 5;
-const SEED_SPAWN_TREE_PROBABILITY: f32 = 0.1;
+const SEED_SPAWN_TREE_PROBABILITY: f32 = 0.4;
+const SPROUT_TIME_SEC: u64 = 8;
+const SAPLING_TIME_SEC: u64 = 12;
+const SPROUT_HEIGHT: f32 = 0.6;
+const SAPLING_HEIGHT: f32 = 1.8;
 
 #[derive(Resource, Asset, Clone, Reflect)]
 pub struct SeedAssets {
     mesh: Handle<Mesh>,
     material: Handle<StandardMaterial>,
+    sprout_mesh: Handle<Mesh>,
+    sprout_material: Handle<StandardMaterial>,
+    sapling_mesh: Handle<Mesh>,
+    sapling_material: Handle<StandardMaterial>,
 }
 
 impl FromWorld for SeedAssets {
     fn from_world(world: &mut World) -> Self {
         let mut meshes = world.get_resource_mut::<Assets<Mesh>>().unwrap();
         let mesh = meshes.add(Sphere::new(SEED_RADIUS));
+        let sprout_mesh = meshes.add(Cone::new(0.25, SPROUT_HEIGHT));
+        let sapling_mesh = meshes.add(Cone::new(0.6, SAPLING_HEIGHT));
 
         let mut materials = world
             .get_resource_mut::<Assets<StandardMaterial>>()
@@ -32,14 +56,89 @@ impl FromWorld for SeedAssets {
         material.emissive = LinearRgba::rgb(0.1, 0.1, 100.);
 
         let material = materials.add(material);
+        let sprout_material = materials.add(StandardMaterial::from_color(GREEN_500));
+        let sapling_material = materials.add(StandardMaterial::from_color(GREEN_700));
 
-        Self { mesh, material }
+        Self {
+            mesh,
+            material,
+            sprout_mesh,
+            sprout_material,
+            sapling_mesh,
+            sapling_material,
+        }
     }
 }
 
 #[derive(Debug, Component)]
 pub struct Seed;
 
+/// A planted seed on its way to become a tree
+#[derive(Debug, Component)]
+pub struct Germination {
+    pub stage: GrowthStage,
+    timer: Timer,
+}
+
+impl Germination {
+    fn new(stage: GrowthStage) -> Self {
+        Self {
+            stage,
+            timer: Timer::new(stage.duration(), TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowthStage {
+    Seed,
+    Sprout,
+    Sapling,
+}
+
+impl GrowthStage {
+    fn duration(self) -> Duration {
+        match self {
+            GrowthStage::Seed => Duration::from_secs(SEED_DESPAWN_TIME_SEC),
+            GrowthStage::Sprout => Duration::from_secs(SPROUT_TIME_SEC),
+            GrowthStage::Sapling => Duration::from_secs(SAPLING_TIME_SEC),
+        }
+    }
+
+    fn health(self) -> u32 {
+        match self {
+            GrowthStage::Seed => 1,
+            GrowthStage::Sprout => 2,
+            GrowthStage::Sapling => 4,
+        }
+    }
+
+    fn height(self) -> f32 {
+        match self {
+            GrowthStage::Seed => SEED_RADIUS * 2.0,
+            GrowthStage::Sprout => SPROUT_HEIGHT,
+            GrowthStage::Sapling => SAPLING_HEIGHT,
+        }
+    }
+
+    /// Everything that makes up the stage, each one is tougher than the last
+    fn bundle(self, assets: &SeedAssets) -> impl Bundle {
+        let (mesh, material) = match self {
+            GrowthStage::Seed => (&assets.mesh, &assets.material),
+            GrowthStage::Sprout => (&assets.sprout_mesh, &assets.sprout_material),
+            GrowthStage::Sapling => (&assets.sapling_mesh, &assets.sapling_material),
+        };
+
+        (
+            Germination::new(self),
+            Health::new(self.health()),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Collider::cylinder(self.height() / 2.0, self.height()),
+        )
+    }
+}
+
 #[derive(Debug, Event)]
 pub struct SeedSpawnEvent {
     pub position: Vec3,
@@ -49,10 +148,11 @@ pub struct SeedSpawnEvent {
 pub(super) fn plugin(app: &mut App) {
     app.add_event::<SeedSpawnEvent>();
     app.init_resource::<SeedAssets>();
+    app.add_observer(uproot_seed);
 
     app.add_systems(
         Update,
-        (spawn_seed, plant_seed)
+        (spawn_seed, plant_seed, grow_seeds, crush_seedlings)
             .run_if(in_state(Screen::InGame))
             .in_set(PausableSystems),
     );
@@ -77,11 +177,15 @@ fn spawn_seed(
             Mass(0.1),
             CollisionEventsEnabled,
             Seed,
+            Magnetic,
+            // seeds that never reach the ground rot away
+            DespawnAfter::millis(SEED_DESPAWN_TIME_SEC * 2000),
             ReplaceOnHotreload,
             Mesh3d(seedasset.mesh.clone()),
             MeshMaterial3d(seedasset.material.clone()),
             RigidBody::Dynamic,
             Collider::sphere(SEED_RADIUS),
+            CollisionLayers::new(GameLayer::Seed, LayerMask::ALL),
             Transform::from_translation(position),
             LinearVelocity(velocity),
             LinearDamping(2.0),
@@ -89,30 +193,122 @@ fn spawn_seed(
     }
 }
 
+/// A seed that lands on the ground stays there and starts to germinate
 fn plant_seed(
     mut commands: Commands,
     mut collision_event_reader: EventReader<CollisionStarted>,
     ground: Single<Entity, With<Ground>>,
-    seeds: Query<(Entity, &Transform), With<Seed>>,
+    seeds: Query<Entity, (With<Seed>, Without<Germination>)>,
+    assets: Res<SeedAssets>,
 ) {
     for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
         for (ground_candidate, seed_candidate) in [(*entity1, *entity2), (*entity2, *entity1)] {
-            if ground_candidate == *ground {
-                if let Ok((seed, transform)) = seeds.get(seed_candidate) {
-                    commands.send_event(TreeSpawnEvent {
-                        position: Vec3::new(
-                            transform.translation.x,
-                            1000.,
-                            transform.translation.z,
-                        ),
-                        startlevel: 0,
-                    });
-
-                    if let Ok(mut ec) = commands.get_entity(seed) {
-                        ec.despawn();
-                    }
-                }
+            if ground_candidate != *ground {
+                continue;
+            }
+
+            if let Ok(seed) = seeds.get(seed_candidate) {
+                commands
+                    .entity(seed)
+                    .remove::<(DespawnAfter, Magnetic)>()
+                    .insert((
+                        GrowthStage::Seed.bundle(&assets),
+                        RigidBody::Static,
+                        Sensor,
+                        Sawable::default(),
+                        // bullets only hit the default layer
+                        CollisionLayers::new(GameLayer::Default, LayerMask::ALL),
+                        AnchoredUiNodes::spawn_one(healthbar(100.)),
+                    ));
             }
         }
     }
 }
+
+fn grow_seeds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut seeds: Query<(Entity, &mut Germination, &mut Transform)>,
+    assets: Res<SeedAssets>,
+    fertility: Res<Fertility>,
+    mut tree_writer: EventWriter<TreeSpawnEvent>,
+) {
+    for (entity, mut germination, mut transform) in seeds.iter_mut() {
+        germination.timer.tick(time.delta());
+        if !germination.timer.just_finished() {
+            continue;
+        }
+
+        // seeds take much better to fertile soil
+        let chance =
+            SEED_SPAWN_TREE_PROBABILITY * (0.5 + fertility.get(transform.translation) * 2.0);
+
+        let next = match germination.stage {
+            GrowthStage::Seed if rand::random::<f32>() > chance => {
+                // did not germinate
+                commands.entity(entity).despawn();
+                continue;
+            }
+            GrowthStage::Seed => GrowthStage::Sprout,
+            GrowthStage::Sprout => GrowthStage::Sapling,
+            GrowthStage::Sapling => {
+                tree_writer.write(TreeSpawnEvent {
+                    position: transform.translation.with_y(1000.),
+                    startlevel: 0,
+                    boss: false,
+                    species: None,
+                });
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+
+        let ground_y = transform.translation.y - germination.stage.height() / 2.0;
+        transform.translation.y = ground_y + next.height() / 2.0;
+        transform.rotation = Quat::IDENTITY;
+        commands.entity(entity).insert(next.bundle(&assets));
+    }
+}
+
+/// Driving over a seedling squashes it, seeds the magnet pulls in are squashed in the air
+fn crush_seedlings(
+    mut collision_event_reader: EventReader<CollisionStarted>,
+    tractor_parts: Query<(), Or<(With<Tractor>, With<Wheel>)>>,
+    seedlings: Query<&Health, With<Seed>>,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
+        for (tractor_candidate, seedling) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            let (Ok(()), Ok(health)) = (
+                tractor_parts.get(tractor_candidate),
+                seedlings.get(seedling),
+            ) else {
+                continue;
+            };
+
+            damage_writer.write(DamageEvent {
+                value: health.current,
+                entity: seedling,
+                source: Some(tractor_candidate),
+                kind: DamageKind::Impact,
+                crit: false,
+            });
+        }
+    }
+}
+
+fn uproot_seed(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    seeds: Query<&Transform, With<Seed>>,
+) {
+    let Ok(transform) = seeds.get(trigger.target()) else {
+        return;
+    };
+
+    commands.spawn((
+        apple_death_particles(),
+        Transform::from_translation(transform.translation).with_scale(Vec3::splat(0.5)),
+    ));
+    commands.entity(trigger.target()).despawn();
+}
@@ -0,0 +1,248 @@
+use crate::PausableSystems;
+use std::collections::HashMap;
+
+use crate::gameplay::apple::{Apple, AppleStrength, SimplifiedMotion};
+use crate::gameplay::apple_kind::AppleKind;
+use crate::gameplay::tractor::Tractor;
+use crate::screens::Screen;
+
+use super::*;
+
+/// How far ahead the tractor's position is predicted at most
+const MAX_PREDICTION_SEC: f32 = 1.5;
+/// Apples closer than this to the tractor stop flanking and go straight in
+const FLANK_CLOSE_DISTANCE: f32 = 8.0;
+/// Extra room kept around obstacles
+const OBSTACLE_MARGIN: f32 = 2.0;
+/// Size of the cells apples are bucketed in, at least the largest separation radius
+const NEIGHBOUR_CELL_SIZE: f32 = 5.0;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Steering>();
+    app.add_systems(
+        Update,
+        steer_apples
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Something apples steer around
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Obstacle {
+    /// radius before the entity is scaled
+    pub radius: f32,
+}
+
+/// Weights of the steering behaviours of an apple, a weight of 0 turns a behaviour off
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Steering {
+    /// towards the tractor, negative to run away from it
+    pub seek: f32,
+    /// how far ahead in seconds the tractor's movement is predicted
+    pub prediction_sec: f32,
+    /// away from other apples
+    pub separation: f32,
+    pub separation_radius: f32,
+    /// around rocks and trees
+    pub avoidance: f32,
+    pub avoidance_radius: f32,
+    /// sideways around the tractor to surround it
+    pub flank: f32,
+    /// which way around the tractor the apple goes, -1 or 1
+    pub flank_side: f32,
+}
+
+impl Default for Steering {
+    fn default() -> Self {
+        Self {
+            seek: 1.0,
+            prediction_sec: 0.5,
+            separation: 0.6,
+            separation_radius: 3.0,
+            avoidance: 1.0,
+            avoidance_radius: 4.0,
+            flank: 0.0,
+            flank_side: 1.0,
+        }
+    }
+}
+
+impl Steering {
+    pub fn for_kind(kind: AppleKind) -> Self {
+        let flank_side = if rand::random::<bool>() { 1.0 } else { -1.0 };
+        let steering = Self {
+            flank_side,
+            ..default()
+        };
+
+        match kind {
+            AppleKind::Normal => Self {
+                flank: 0.4,
+                ..steering
+            },
+            AppleKind::Golden => Self {
+                seek: -1.0,
+                prediction_sec: 0.0,
+                separation: 0.2,
+                avoidance: 1.5,
+                ..steering
+            },
+            AppleKind::Rotten => Self {
+                prediction_sec: 1.0,
+                separation: 1.0,
+                separation_radius: 5.0,
+                ..steering
+            },
+            AppleKind::Splitter => Self {
+                flank: 0.8,
+                ..steering
+            },
+            AppleKind::Bouncer => Self {
+                avoidance: 0.3,
+                ..steering
+            },
+            AppleKind::Armored => Self {
+                prediction_sec: 0.0,
+                separation: 0.3,
+                ..steering
+            },
+        }
+    }
+
+    fn seek(&self, position: Vec3, target: Vec3, target_velocity: Vec3) -> Vec3 {
+        let distance = position.distance(target);
+        // predict less when already close, otherwise the apples overshoot
+        let lookahead = self
+            .prediction_sec
+            .min(distance / 10.0)
+            .min(MAX_PREDICTION_SEC);
+        let predicted = target + target_velocity * lookahead;
+
+        (predicted - position).normalize_or_zero() * self.seek
+    }
+
+    fn flank(&self, position: Vec3, target: Vec3) -> Vec3 {
+        let to_target = (target - position).with_y(0.0);
+        let distance = to_target.length();
+        if distance < FLANK_CLOSE_DISTANCE {
+            return Vec3::ZERO;
+        }
+
+        let sideways = Vec3::Y.cross(to_target / distance) * self.flank_side;
+        // flank hardest when far away, fade out towards the tractor
+        let fade = ((distance - FLANK_CLOSE_DISTANCE) / FLANK_CLOSE_DISTANCE).min(1.0);
+
+        sideways * self.flank * fade
+    }
+
+    fn separation(&self, position: Vec3, neighbours: &Neighbours, me: Entity) -> Vec3 {
+        let mut push = Vec3::ZERO;
+
+        for (other, other_position) in neighbours.around(position) {
+            if *other == me {
+                continue;
+            }
+
+            let away = (position - *other_position).with_y(0.0);
+            let distance = away.length();
+            if distance > 0.0 && distance < self.separation_radius {
+                push += away / distance * (1.0 - distance / self.separation_radius);
+            }
+        }
+
+        push.clamp_length_max(1.0) * self.separation
+    }
+
+    fn avoidance(&self, position: Vec3, obstacles: &[(Vec3, f32)]) -> Vec3 {
+        let mut push = Vec3::ZERO;
+
+        for (obstacle_position, radius) in obstacles {
+            let away = (position - *obstacle_position).with_y(0.0);
+            let reach = radius + self.avoidance_radius;
+            let distance = away.length();
+            if distance > 0.0 && distance < reach {
+                push += away / distance * (1.0 - distance / reach);
+            }
+        }
+
+        push.clamp_length_max(1.0) * self.avoidance
+    }
+}
+
+/// Apples bucketed by position, so each apple only looks at the ones around it
+struct Neighbours(HashMap<IVec2, Vec<(Entity, Vec3)>>);
+
+impl Neighbours {
+    fn cell(position: Vec3) -> IVec2 {
+        (position.xz() / NEIGHBOUR_CELL_SIZE).floor().as_ivec2()
+    }
+
+    fn new(apples: impl Iterator<Item = (Entity, Vec3)>) -> Self {
+        let mut cells: HashMap<IVec2, Vec<(Entity, Vec3)>> = HashMap::new();
+        for (entity, position) in apples {
+            cells
+                .entry(Self::cell(position))
+                .or_default()
+                .push((entity, position));
+        }
+        Self(cells)
+    }
+
+    fn around(&self, position: Vec3) -> impl Iterator<Item = &(Entity, Vec3)> {
+        let center = Self::cell(position);
+        (-1..=1)
+            .flat_map(move |x| (-1..=1).map(move |z| center + IVec2::new(x, z)))
+            .filter_map(|cell| self.0.get(&cell))
+            .flatten()
+    }
+}
+
+#[cfg_attr(feature = "dev_native", hot)]
+fn steer_apples(
+    mut apples: Query<
+        (
+            Entity,
+            &Transform,
+            &AppleStrength,
+            &Steering,
+            &mut ExternalForce,
+            Has<SimplifiedMotion>,
+        ),
+        With<Apple>,
+    >,
+    tractor: Single<(&Transform, &LinearVelocity), (With<Tractor>, Without<Apple>)>,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
+) {
+    let (tractor_t, tractor_v) = *tractor;
+
+    let neighbours = Neighbours::new(
+        apples
+            .iter()
+            .map(|(entity, transform, ..)| (entity, transform.translation)),
+    );
+    let obstacles: Vec<(Vec3, f32)> = obstacles
+        .iter()
+        .map(|(transform, obstacle)| {
+            (
+                transform.translation(),
+                obstacle.radius * transform.scale().x + OBSTACLE_MARGIN,
+            )
+        })
+        .collect();
+
+    for (entity, transform, strength, steering, mut force, simplified) in apples.iter_mut() {
+        let position = transform.translation;
+
+        let mut desired = steering.seek(position, tractor_t.translation, tractor_v.0)
+            + steering.flank(position, tractor_t.translation);
+        // far away apples don't collide, so they only need to find the tractor
+        if !simplified {
+            desired += steering.separation(position, &neighbours, entity)
+                + steering.avoidance(position, &obstacles);
+        }
+
+        let max_force = strength.speed as f32 * 1.3 + 5.;
+        force.set_force(desired.clamp_length_max(1.0) * max_force);
+    }
+}
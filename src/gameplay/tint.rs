@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use bevy::scene::SceneInstanceReady;
+
+use super::*;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<TintedMaterials>();
+    app.add_observer(tint_scene);
+}
+
+/// Multiplies the base color of every material in the entity's scene
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Tint(pub Color);
+
+/// Tinted copies of materials, so every entity with the same tint shares them
+#[derive(Resource, Default)]
+struct TintedMaterials(HashMap<(AssetId<StandardMaterial>, [u8; 4]), Handle<StandardMaterial>>);
+
+/// Scenes can be swapped at runtime, so the tint is applied every time one is ready
+fn tint_scene(
+    trigger: Trigger<SceneInstanceReady>,
+    tints: Query<&Tint>,
+    children: Query<&Children>,
+    mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut cache: ResMut<TintedMaterials>,
+) {
+    let Ok(Tint(tint)) = tints.get(trigger.target()) else {
+        return;
+    };
+
+    for child in children.iter_descendants(trigger.target()) {
+        let Ok(mut mesh_material) = mesh_materials.get_mut(child) else {
+            continue;
+        };
+
+        let key = (mesh_material.id(), tint.to_srgba().to_u8_array());
+        if let Some(handle) = cache.0.get(&key) {
+            mesh_material.0 = handle.clone();
+            continue;
+        }
+
+        let Some(material) = materials.get(&mesh_material.0).cloned() else {
+            continue;
+        };
+
+        let handle = materials.add(tinted(&material, *tint));
+        cache.0.insert(key, handle.clone());
+        mesh_material.0 = handle;
+    }
+}
+
+/// Copy of the material with its base color multiplied by the tint
+pub fn tinted(material: &StandardMaterial, tint: Color) -> StandardMaterial {
+    let base = material.base_color.to_linear();
+    let tint = tint.to_linear();
+
+    StandardMaterial {
+        base_color: LinearRgba::new(
+            base.red * tint.red,
+            base.green * tint.green,
+            base.blue * tint.blue,
+            base.alpha,
+        )
+        .into(),
+        ..material.clone()
+    }
+}
@@ -1,23 +1,29 @@
+use std::f32::consts::{FRAC_PI_2, PI};
 use std::time::Duration;
 
 use super::*;
+use crate::gameplay::ability::AbilitySlot;
 use crate::gameplay::apple::Apple;
-use crate::gameplay::health::{Death, Health};
+use crate::gameplay::attachment::{Magnet, magnet, spawn_plow};
+use crate::gameplay::health::{Critical, DamageKind, Death, Health};
 use crate::gameplay::level::TERRAIN_HEIGHT;
+use crate::gameplay::lives::LastLifeLost;
+use crate::gameplay::loadout::{Attachment, Loadout};
+use crate::gameplay::parts::{Part, part};
+use crate::gameplay::saw::SawHeat;
+use crate::gameplay::trailer::spawn_trailer;
+use crate::gameplay::vehicle::{Suspension, VehicleControls, VehicleTuning, WheelHub};
+use crate::gameplay::vehicle_kind::VehicleSpec;
 use crate::{ReplaceOnHotreload, asset_tracking::LoadResource};
 use avian3d::prelude::*;
 use bevy_tweening::lens::{TransformPositionLens, TransformRotateXLens};
 use bevy_tweening::{Animator, RepeatCount, RepeatStrategy, Sequence, Tween};
 
-pub const TRACTOR_WIDTH: f32 = 1.0;
-pub const TRACTOR_HEIGHT: f32 = 2.0;
-pub const TRACTOR_LENGTH: f32 = 4.0;
-
-pub const TRACTOR_MAX_SPEED: f32 = 15.0;
-
-pub const WHEEL_RADIE: f32 = 0.4;
+pub const WHEEL_HUB_MASS: f32 = 0.2;
 pub const SAW_DEFAULT_RRATE_OF_FIRE: Duration = Duration::from_millis(500);
 pub const SAW_DEFAULT_DAMAGE: u32 = 1;
+pub const SAW_DEFAULT_CRIT_CHANCE: f32 = 0.05;
+pub const SAW_DEFAULT_CRIT_MULTIPLIER: f32 = 3.0;
 
 pub fn tractor_plugin(app: &mut App) {
     app.load_resource::<TractorAssets>();
@@ -85,10 +91,22 @@ pub struct Wheel;
 #[derive(Component)]
 pub struct Tractor;
 
-#[derive(Component)]
+#[derive(Component, Clone, Debug)]
 pub struct TractorSaw {
     pub rate_of_fire: Duration,
     pub damage: u32,
+    /// blade width multiplier
+    pub width: f32,
+}
+
+impl Default for TractorSaw {
+    fn default() -> Self {
+        Self {
+            rate_of_fire: SAW_DEFAULT_RRATE_OF_FIRE,
+            damage: SAW_DEFAULT_DAMAGE,
+            width: 1.0,
+        }
+    }
 }
 
 pub fn spawn_tractor<T: Bundle>(
@@ -96,13 +114,17 @@ pub fn spawn_tractor<T: Bundle>(
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
     assets: &TractorAssets,
+    loadout: &Loadout,
     extra_components: T,
 ) -> Entity {
+    let spec = loadout.vehicle.spec();
     let tractor_id = commands
-        .spawn((tractor_body(assets), extra_components))
+        .spawn((tractor_body(assets, loadout), extra_components))
         .observe(
-            |trigger: Trigger<Death>, mut commands: Commands, mut writer: EventWriter<GameOver>| {
-                // gameover when tractor dies
+            |trigger: Trigger<LastLifeLost>,
+             mut commands: Commands,
+             mut writer: EventWriter<GameOver>| {
+                // gameover when the tractor is out of lives
                 writer.write(GameOver);
 
                 commands
@@ -111,59 +133,138 @@ pub fn spawn_tractor<T: Bundle>(
                     .despawn();
             },
         )
-        .with_child(turret::turret(
-            meshes,
-            materials,
-            Vec3::new(
-                0.0,
-                tractor::TRACTOR_HEIGHT / 2.0 + turret::BODY_RADIE + WHEEL_RADIE,
-                0.75,
-            ),
-        ))
         .id();
 
-    spawn_tractor_saw(assets, tractor_id, commands);
+    for mount in loadout.turrets.iter() {
+        commands.entity(tractor_id).with_child((
+            turret::turret(meshes, materials, mount.position(spec)),
+            part(Part::Turret),
+        ));
+    }
+
+    if loadout.has(Attachment::Saw) {
+        spawn_tractor_saw(
+            assets,
+            spec,
+            tractor_id,
+            commands,
+            SawMount::Front,
+            TractorSaw::default(),
+            SawHeat::default(),
+        );
+    }
 
-    let wheel_offset_x = TRACTOR_WIDTH / 2.0 + 0.2 + WHEEL_RADIE;
-    let wheel_offset_z = TRACTOR_LENGTH / 2.0 - WHEEL_RADIE - 0.2;
-    let wheel_offset_y = -TRACTOR_HEIGHT / 2.0 + WHEEL_RADIE / 2. + 0.1;
+    if loadout.has(Attachment::Plow) {
+        spawn_plow(commands, meshes, materials, spec, tractor_id);
+    }
 
-    let wheel_pos = Vec3::new(-wheel_offset_x, wheel_offset_y, wheel_offset_z);
-    left_wheel_with_joint(commands, ReplaceOnHotreload, tractor_id, wheel_pos, 0.8);
+    if loadout.has(Attachment::Magnet) {
+        commands
+            .entity(tractor_id)
+            .insert(Magnet::default())
+            .with_child(magnet(meshes, materials, spec));
+    }
 
-    let wheel_pos = Vec3::new(wheel_offset_x, wheel_offset_y, wheel_offset_z);
-    left_wheel_with_joint(commands, ReplaceOnHotreload, tractor_id, wheel_pos, 0.8);
+    if loadout.has(Attachment::Trailer) {
+        spawn_trailer(commands, meshes, materials, spec, tractor_id);
+    }
 
-    let wheel_pos = Vec3::new(-wheel_offset_x, wheel_offset_y, -wheel_offset_z);
-    right_wheel_with_joint(commands, ReplaceOnHotreload, tractor_id, wheel_pos, 0.8);
+    let tuning = loadout.tuning();
+    let radius = spec.wheel_radius;
 
-    let wheel_pos = Vec3::new(wheel_offset_x, wheel_offset_y, -wheel_offset_z);
-    right_wheel_with_joint(commands, ReplaceOnHotreload, tractor_id, wheel_pos, 0.8);
+    for wheel_pos in spec.left_wheels() {
+        left_wheel_with_joint(
+            commands,
+            ReplaceOnHotreload,
+            tractor_id,
+            wheel_pos,
+            radius,
+            &tuning,
+        );
+
+        let wheel_pos = wheel_pos.with_x(-wheel_pos.x);
+        right_wheel_with_joint(
+            commands,
+            ReplaceOnHotreload,
+            tractor_id,
+            wheel_pos,
+            radius,
+            &tuning,
+        );
+    }
 
     tractor_id
 }
 
+/// A hub that springs up and down under the body, the wheel spins on it
+fn suspended_hub<T: Bundle + Clone>(
+    commands: &mut Commands<'_, '_>,
+    extra_components: T,
+    tractor_id: Entity,
+    wheel_pos: Vec3,
+    radius: f32,
+    suspension: Suspension,
+) -> Entity {
+    let hub = commands
+        .spawn((
+            Name::new("WheelHub"),
+            WheelHub {
+                vehicle: tractor_id,
+                anchor: wheel_pos,
+            },
+            suspension,
+            RigidBody::Dynamic,
+            Mass(WHEEL_HUB_MASS),
+            AngularInertia::new(Vec3::splat(WHEEL_HUB_MASS * radius * radius)),
+            Transform::from_translation(wheel_pos),
+            extra_components.clone(),
+        ))
+        .id();
+
+    commands.spawn((
+        PrismaticJoint::new(tractor_id, hub)
+            .with_local_anchor_1(wheel_pos)
+            .with_local_anchor_2(Vec3::ZERO)
+            .with_free_axis(Vec3::Y)
+            .with_limits(-suspension.travel, suspension.travel),
+        extra_components,
+    ));
+
+    hub
+}
+
 fn left_wheel_with_joint<T: Bundle + Clone>(
     commands: &mut Commands<'_, '_>,
     extra_components: T,
     tractor_id: Entity,
     wheel_pos: Vec3,
-    friction: f32,
+    radius: f32,
+    tuning: &VehicleTuning,
 ) {
+    let hub = suspended_hub(
+        commands,
+        extra_components.clone(),
+        tractor_id,
+        wheel_pos,
+        radius,
+        tuning.suspension,
+    );
+
     let front_left_wheel = commands
         .spawn((
-            wheel(WHEEL_RADIE, wheel_pos),
+            wheel(radius, wheel_pos),
             LeftWheel {
                 vehicle: tractor_id,
             },
-            Friction::new(friction),
+            Friction::new(tuning.traction),
+            tractor_collision_layers(),
             extra_components.clone(),
         ))
         .id();
 
     commands.spawn((
-        RevoluteJoint::new(tractor_id, front_left_wheel)
-            .with_local_anchor_1(wheel_pos)
+        RevoluteJoint::new(hub, front_left_wheel)
+            .with_local_anchor_1(Vec3::ZERO)
             .with_local_anchor_2(Vec3::ZERO)
             .with_angular_velocity_damping(0.0)
             .with_aligned_axis(-Vec3::X),
@@ -175,22 +276,33 @@ fn right_wheel_with_joint<T: Bundle + Clone>(
     extra_components: T,
     tractor_id: Entity,
     wheel_pos: Vec3,
-    friction: f32,
+    radius: f32,
+    tuning: &VehicleTuning,
 ) {
+    let hub = suspended_hub(
+        commands,
+        extra_components.clone(),
+        tractor_id,
+        wheel_pos,
+        radius,
+        tuning.suspension,
+    );
+
     let front_left_wheel = commands
         .spawn((
-            wheel(WHEEL_RADIE, wheel_pos),
+            wheel(radius, wheel_pos),
             RightWheel {
                 vehicle: tractor_id,
             },
-            Friction::new(friction),
+            Friction::new(tuning.traction),
+            tractor_collision_layers(),
             extra_components.clone(),
         ))
         .id();
 
     commands.spawn((
-        RevoluteJoint::new(tractor_id, front_left_wheel)
-            .with_local_anchor_1(wheel_pos)
+        RevoluteJoint::new(hub, front_left_wheel)
+            .with_local_anchor_1(Vec3::ZERO)
             .with_local_anchor_2(Vec3::ZERO)
             .with_angular_velocity_damping(0.0)
             .with_aligned_axis(Vec3::X),
@@ -198,51 +310,121 @@ fn right_wheel_with_joint<T: Bundle + Clone>(
     ));
 }
 
-pub fn tractor_body(assets: &TractorAssets) -> impl Bundle {
+/// Collision layers for every physics part of the tractor
+pub fn tractor_collision_layers() -> CollisionLayers {
+    CollisionLayers::new(GameLayer::Tractor, LayerMask::ALL)
+}
+
+pub fn tractor_body(assets: &TractorAssets, loadout: &Loadout) -> impl Bundle {
+    let spec = loadout.vehicle.spec();
+    let stats = loadout.stats();
+    let body = spec.body_size();
+    let scale = spec.model_scale;
+
     (
         Tractor,
-        MaxLinearSpeed(TRACTOR_MAX_SPEED),
-        Name::new("Tractor"),
-        CollisionEventsEnabled,
+        loadout.vehicle,
+        Name::new(spec.name),
         children![(
             Transform {
-                translation: vec3(0.0, -TRACTOR_HEIGHT / 2. - 0.1, 0.2),
+                translation: vec3(0.0, -spec.top() - 0.1 * scale.y, 0.2 * scale.z),
                 rotation: Quat::from_rotation_y(-90_f32.to_radians()),
-                ..default()
+                // the model is turned sideways, so its x runs along the vehicle
+                scale: vec3(scale.z, scale.y, scale.x),
             },
             SceneRoot(assets.tractor.clone()),
         ),],
-        RigidBody::Dynamic,
-        Health::new(5),
-        CenterOfMass::new(0.0, -TRACTOR_HEIGHT / 2.0, 0.0),
-        Collider::cuboid(
-            TRACTOR_WIDTH,
-            TRACTOR_HEIGHT,
-            TRACTOR_LENGTH - WHEEL_RADIE * 2.,
+        // driving
+        (
+            VehicleControls::default(),
+            AbilitySlot::new(spec.ability),
+            loadout.tuning(),
+        ),
+        // damage, the body doubles as the engine
+        (Health::new(stats.health), part(Part::Engine)),
+        // physics
+        (
+            RigidBody::Dynamic,
+            MaxLinearSpeed(stats.top_speed),
+            // the suspension pushes on the body every physics step
+            ExternalForce::default().with_persistence(false),
+            ColliderDensity(spec.density * loadout.chassis.density()),
+            CenterOfMass::new(0.0, -spec.top(), 0.0),
+            Collider::cuboid(body.x, body.y, body.z),
+            CollidingEntities::default(),
+            CollisionEventsEnabled,
+            tractor_collision_layers(),
         ),
-        CollidingEntities::default(),
     )
 }
 
-pub fn spawn_tractor_saw(assets: &TractorAssets, tractor_id: Entity, commands: &mut Commands) {
+/// Where on the tractor a saw is mounted
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SawMount {
+    Front,
+    Left,
+    Right,
+    Rear,
+}
+
+impl SawMount {
+    /// The order extra saws are mounted in when upgrading
+    pub const EXTRA_MOUNTS: [SawMount; 3] = [SawMount::Left, SawMount::Right, SawMount::Rear];
+
+    fn position(&self, spec: &VehicleSpec) -> Vec3 {
+        let side_x = spec.half_track() + 0.5;
+        let offset_x = spec.size.x / 2.0 - spec.wheel_radius;
+        match self {
+            SawMount::Front => Vec3::new(-offset_x, 0.0, -(spec.size.z / 2.0)),
+            SawMount::Rear => Vec3::new(offset_x, 0.0, spec.size.z / 2.0),
+            SawMount::Left => Vec3::new(-side_x, 0.0, 0.0),
+            SawMount::Right => Vec3::new(side_x, 0.0, 0.0),
+        }
+    }
+
+    fn rotation(&self) -> Quat {
+        match self {
+            SawMount::Front => Quat::IDENTITY,
+            SawMount::Rear => Quat::from_rotation_y(PI),
+            SawMount::Left => Quat::from_rotation_y(FRAC_PI_2),
+            SawMount::Right => Quat::from_rotation_y(-FRAC_PI_2),
+        }
+    }
+
+    /// size of the saw collider
+    pub fn size(&self, spec: &VehicleSpec) -> Vec3 {
+        (self.rotation() * Vec3::new(self.length(spec), 0.5, 0.5)).abs()
+    }
+
+    fn length(&self, spec: &VehicleSpec) -> f32 {
+        match self {
+            SawMount::Front | SawMount::Rear => (spec.size.x + spec.wheel_radius * 2.0) * 2.0 - 1.0,
+            SawMount::Left | SawMount::Right => spec.size.z / 2.0,
+        }
+    }
+
+    /// the scale of the saw body for the given width upgrade, only stretches along the blade
+    pub fn scale(&self, width: f32) -> Vec3 {
+        (self.rotation() * Vec3::new(width, 1.0, 1.0)).abs()
+    }
+}
+
+pub fn spawn_tractor_saw(
+    assets: &TractorAssets,
+    spec: &VehicleSpec,
+    tractor_id: Entity,
+    commands: &mut Commands,
+    mount: SawMount,
+    saw: TractorSaw,
+    heat: SawHeat,
+) {
     let animation_length_x = 1.5;
 
-    let saw_pos = Vec3::new(
-        -(TRACTOR_WIDTH / 2.0 - WHEEL_RADIE),
-        0.0,
-        -(TRACTOR_LENGTH / 2.0),
-    );
+    let saw_pos = mount.position(spec);
+    let blade = mount.rotation() * Vec3::X;
 
-    let saw_pos_1 = Vec3::new(
-        -(TRACTOR_WIDTH / 2.0 - WHEEL_RADIE) + animation_length_x / 2.,
-        0.0,
-        0.0, //-(TRACTOR_LENGTH / 2.0),
-    );
-    let saw_pos_2 = Vec3::new(
-        -(TRACTOR_WIDTH / 2.0 - WHEEL_RADIE) - animation_length_x / 2.,
-        0.0,
-        0.0, //-(TRACTOR_LENGTH / 2.0),
-    );
+    let saw_pos_1 = blade * animation_length_x / 2.;
+    let saw_pos_2 = -blade * animation_length_x / 2.;
 
     let animation = Tween::new(
         EaseFunction::Linear,
@@ -255,23 +437,27 @@ pub fn spawn_tractor_saw(assets: &TractorAssets, tractor_id: Entity, commands: &
     .with_repeat_strategy(RepeatStrategy::MirroredRepeat)
     .with_repeat_count(RepeatCount::Infinite);
 
+    let size = mount.size(spec);
+
     let saw = commands
         .spawn((
-            TractorSaw {
-                rate_of_fire: SAW_DEFAULT_RRATE_OF_FIRE,
-                damage: SAW_DEFAULT_DAMAGE,
-            },
+            Critical::new(SAW_DEFAULT_CRIT_CHANCE, SAW_DEFAULT_CRIT_MULTIPLIER),
             ReplaceOnHotreload,
             CollisionEventsEnabled,
             Name::new("TractorSaw"),
             RigidBody::Dynamic,
-            Transform::from_translation(saw_pos),
-            Collider::cuboid((TRACTOR_WIDTH + WHEEL_RADIE * 2.0) * 2.0 - 1.0, 0.5, 0.5),
+            Transform::from_translation(saw_pos).with_scale(mount.scale(saw.width)),
+            Collider::cuboid(size.x, size.y, size.z),
+            tractor_collision_layers(),
             children![(
                 SceneRoot(assets.saw.clone()),
                 Animator::new(animation),
-                Transform::from_translation(saw_pos_1),
+                Transform::from_translation(saw_pos_1).with_rotation(mount.rotation()),
             )],
+            saw,
+            heat,
+            mount,
+            part(Part::Saw),
         ))
         .id();
 
@@ -288,6 +474,7 @@ pub fn wheel(radius: f32, pos: Vec3) -> impl Bundle {
         CollidingEntities::default(),
         RigidBody::Dynamic,
         Wheel,
+        part(Part::Wheel),
         Collider::sphere(radius),
         Transform {
             translation: pos,
@@ -303,7 +490,13 @@ fn kill_tractor_below_map(
 ) {
     for (entity, transform) in query.iter() {
         if transform.translation.y < -1. * TERRAIN_HEIGHT {
-            commands.trigger_targets(Death, entity);
+            commands.trigger_targets(
+                Death {
+                    source: None,
+                    kind: DamageKind::Fall,
+                },
+                entity,
+            );
         }
     }
 }
@@ -0,0 +1,371 @@
+use crate::gameplay::apple::Apple;
+use crate::gameplay::health::Death;
+use crate::gameplay::level::{PLANE_Z_SIZE, terrain_height};
+use crate::gameplay::pickup::{PICKUP_LIFETIME_MS, PickedUp, Pickup, PickupAssets, pickup};
+use crate::gameplay::score::Currency;
+use crate::gameplay::vehicle_kind::VehicleSpec;
+use crate::screens::Screen;
+use crate::{PausableSystems, ReplaceOnHotreload};
+use bevy::color::palettes::tailwind::{AMBER_800, RED_500, RED_800, STONE_600, ZINC_200};
+
+use super::*;
+
+pub const TRAILER_MASS: f32 = 3.0;
+const TRAILER_WIDTH: f32 = 2.4;
+const TRAILER_LENGTH: f32 = 3.0;
+const TRAILER_WALL_HEIGHT: f32 = 0.6;
+const TRAILER_WALL_THICKNESS: f32 = 0.1;
+const TRAILER_WHEEL_RADIUS: f32 = 0.5;
+/// The wheels slide instead of rolling, so they barely grip
+const TRAILER_WHEEL_FRICTION: f32 = 0.05;
+/// Length of the bar between the hitch and the bed
+const TRAILER_TONGUE: f32 = 1.0;
+const CARGO_RADIUS: f32 = 0.3;
+const CARGO_MASS: f32 = 0.1;
+const FRUIT_CHANCE: f32 = 0.4;
+/// Upwards speed given to the cargo when an apple hits the trailer
+const CARGO_KNOCK: f32 = 5.0;
+/// The barn sits this far out from the middle, as a share of the map size
+const BARN_SPREAD: f32 = 0.1;
+const BARN_SIZE: Vec3 = Vec3::new(8.0, 5.0, 6.0);
+/// The trailer has to be parked this close to the barn door to unload
+const BARN_YARD_RADIUS: f32 = 5.0;
+/// Fruit delivered for one currency
+const FRUIT_PER_CURRENCY: u32 = 5;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<TrailerAssets>();
+
+    app.add_systems(OnEnter(Screen::InGame), spawn_barn);
+    app.add_systems(
+        Update,
+        (knock_cargo_out, spill_cargo, deliver_cargo)
+            .chain()
+            .run_if(in_state(Screen::InGame))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(drop_fruit).add_observer(load_fruit);
+}
+
+/// Towed behind a vehicle, carries fruit to the barn
+#[derive(Component, Debug)]
+pub struct Trailer {
+    pub vehicle: Entity,
+}
+
+/// Fruit lying in a trailer bed
+#[derive(Component, Debug)]
+pub struct Cargo;
+
+/// Buys the fruit trailers bring to its yard
+#[derive(Component, Debug, Default)]
+struct Barn {
+    delivered: u32,
+}
+
+#[derive(Resource)]
+struct TrailerAssets {
+    cargo_mesh: Handle<Mesh>,
+    cargo_material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for TrailerAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let cargo_mesh = meshes.add(Sphere::new(CARGO_RADIUS));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let cargo_material = materials.add(StandardMaterial::from_color(RED_500));
+
+        Self {
+            cargo_mesh,
+            cargo_material,
+        }
+    }
+}
+
+/// The trailer drags along on the tractor layer but never bumps into the tractor
+fn trailer_collision_layers() -> CollisionLayers {
+    CollisionLayers::new(
+        GameLayer::Tractor,
+        [GameLayer::Default, GameLayer::Cargo, GameLayer::Seed],
+    )
+}
+
+/// Cargo can't be shot, but apples, the tractor and other cargo push it around
+fn cargo_collision_layers() -> CollisionLayers {
+    CollisionLayers::new(
+        GameLayer::Cargo,
+        [GameLayer::Default, GameLayer::Tractor, GameLayer::Cargo],
+    )
+}
+
+/// Hitches a trailer behind the vehicle
+pub fn spawn_trailer(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    spec: &VehicleSpec,
+    tractor_id: Entity,
+) {
+    // the trailer wheels hang below the bed floor
+    let ground = -spec.top() - spec.wheel_radius / 2.0 + 0.1;
+    let floor = ground + TRAILER_WHEEL_RADIUS + 0.3;
+    let hitch = Vec3::new(0.0, floor, spec.size.z / 2.0 + 0.3);
+    let tongue = Vec3::new(0.0, 0.0, -(TRAILER_LENGTH / 2.0 + TRAILER_TONGUE));
+
+    let wood = materials.add(StandardMaterial::from_color(AMBER_800));
+    let wheel_mesh = meshes.add(Sphere::new(TRAILER_WHEEL_RADIUS));
+    let wheel_material = materials.add(StandardMaterial::from_color(STONE_600));
+
+    let trailer = commands
+        .spawn((
+            Name::new("Trailer"),
+            Trailer {
+                vehicle: tractor_id,
+            },
+            ReplaceOnHotreload,
+            RigidBody::Dynamic,
+            Mass(TRAILER_MASS),
+            Transform::from_translation(hitch - tongue),
+        ))
+        .id();
+
+    let wall_y = TRAILER_WALL_HEIGHT / 2.0;
+    let side_x = TRAILER_WIDTH / 2.0;
+    let end_z = TRAILER_LENGTH / 2.0;
+    let side = Vec3::new(TRAILER_WALL_THICKNESS, TRAILER_WALL_HEIGHT, TRAILER_LENGTH);
+    let end = Vec3::new(TRAILER_WIDTH, TRAILER_WALL_HEIGHT, TRAILER_WALL_THICKNESS);
+    let bed = [
+        (Vec3::new(TRAILER_WIDTH, 0.2, TRAILER_LENGTH), Vec3::ZERO),
+        (side, Vec3::new(-side_x, wall_y, 0.0)),
+        (side, Vec3::new(side_x, wall_y, 0.0)),
+        (end, Vec3::new(0.0, wall_y, -end_z)),
+        (end, Vec3::new(0.0, wall_y, end_z)),
+    ];
+
+    for (size, position) in bed {
+        commands.entity(trailer).with_child((
+            Collider::cuboid(size.x, size.y, size.z),
+            trailer_collision_layers(),
+            CollisionEventsEnabled,
+            Mesh3d(meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(wood.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+
+    let wheel_x = side_x + TRAILER_WHEEL_RADIUS;
+    for x in [-wheel_x, wheel_x] {
+        commands.entity(trailer).with_child((
+            Collider::sphere(TRAILER_WHEEL_RADIUS),
+            Friction::new(TRAILER_WHEEL_FRICTION),
+            trailer_collision_layers(),
+            Mesh3d(wheel_mesh.clone()),
+            MeshMaterial3d(wheel_material.clone()),
+            Transform::from_xyz(x, -0.3, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        ReplaceOnHotreload,
+        SphericalJoint::new(tractor_id, trailer)
+            .with_local_anchor_1(hitch)
+            .with_local_anchor_2(tongue),
+    ));
+}
+
+fn cargo(assets: &TrailerAssets, position: Vec3) -> impl Bundle {
+    (
+        Name::new("Cargo"),
+        Cargo,
+        ReplaceOnHotreload,
+        StateScoped(Screen::InGame),
+        RigidBody::Dynamic,
+        Mass(CARGO_MASS),
+        Collider::sphere(CARGO_RADIUS),
+        cargo_collision_layers(),
+        Mesh3d(assets.cargo_mesh.clone()),
+        MeshMaterial3d(assets.cargo_material.clone()),
+        Transform::from_translation(position),
+    )
+}
+
+/// Whether `position` is above the bed of the trailer, however high
+fn in_bed(trailer: &GlobalTransform, position: Vec3) -> bool {
+    let local = trailer.affine().inverse().transform_point3(position);
+    local.x.abs() < TRAILER_WIDTH / 2.0 && local.z.abs() < TRAILER_LENGTH / 2.0 && local.y > -0.5
+}
+
+/// Dead apples sometimes leave fruit behind, when there is a trailer to carry it
+fn drop_fruit(
+    trigger: Trigger<Death>,
+    mut commands: Commands,
+    apples: Query<&Transform, With<Apple>>,
+    trailers: Query<(), With<Trailer>>,
+    assets: Res<PickupAssets>,
+) {
+    let Ok(transform) = apples.get(trigger.target()) else {
+        return;
+    };
+
+    if !trailers.is_empty() && rand::random::<f32>() < FRUIT_CHANCE {
+        let position = transform.translation.with_y(terrain_height(
+            transform.translation.x,
+            transform.translation.z,
+        ));
+        commands.spawn((
+            pickup(&assets, Pickup::Fruit, position),
+            DespawnAfter::millis(PICKUP_LIFETIME_MS),
+        ));
+    }
+}
+
+/// Drops picked up fruit into the bed of the vehicle's trailer
+fn load_fruit(
+    trigger: Trigger<PickedUp>,
+    mut commands: Commands,
+    trailers: Query<(&Trailer, &GlobalTransform)>,
+    assets: Res<TrailerAssets>,
+) {
+    if trigger.0 != Pickup::Fruit {
+        return;
+    }
+
+    let Some((_, trailer_t)) = trailers
+        .iter()
+        .find(|(trailer, _)| trailer.vehicle == trigger.target())
+    else {
+        return;
+    };
+
+    let spread = Vec3::new(
+        (rand::random::<f32>() - 0.5) * (TRAILER_WIDTH - CARGO_RADIUS * 2.0),
+        TRAILER_WALL_HEIGHT + CARGO_RADIUS * 2.0,
+        (rand::random::<f32>() - 0.5) * (TRAILER_LENGTH - CARGO_RADIUS * 2.0),
+    );
+    commands.spawn(cargo(&assets, trailer_t.transform_point(spread)));
+}
+
+/// Apples slamming into the trailer throw the cargo up, some of it flies out
+fn knock_cargo_out(
+    mut collision_event_reader: EventReader<CollisionStarted>,
+    apples: Query<(), With<Apple>>,
+    trailers: Query<&GlobalTransform, With<Trailer>>,
+    parents: Query<&ChildOf>,
+    mut cargo: Query<(&Transform, &mut LinearVelocity), With<Cargo>>,
+) {
+    for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
+        for (apple, other) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if !apples.contains(apple) {
+                continue;
+            }
+            // the bed is made of child colliders
+            let bed = parents.get(other).map_or(other, ChildOf::parent);
+            let Ok(trailer_t) = trailers.get(bed) else {
+                continue;
+            };
+
+            for (transform, mut velocity) in cargo.iter_mut() {
+                if !in_bed(trailer_t, transform.translation) {
+                    continue;
+                }
+                let sideways = Vec3::new(
+                    rand::random::<f32>() - 0.5,
+                    0.0,
+                    rand::random::<f32>() - 0.5,
+                );
+                velocity.0 += (Vec3::Y + sideways) * CARGO_KNOCK;
+            }
+        }
+    }
+}
+
+/// Cargo that left the trailer lands on the ground as fruit again
+fn spill_cargo(
+    mut commands: Commands,
+    cargo: Query<(Entity, &Transform), With<Cargo>>,
+    trailers: Query<&GlobalTransform, With<Trailer>>,
+    assets: Res<PickupAssets>,
+) {
+    for (entity, transform) in cargo.iter() {
+        let position = transform.translation;
+        if trailers.iter().any(|trailer_t| in_bed(trailer_t, position)) {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        let ground = position.with_y(terrain_height(position.x, position.z));
+        commands.spawn((
+            pickup(&assets, Pickup::Fruit, ground),
+            DespawnAfter::millis(PICKUP_LIFETIME_MS),
+        ));
+    }
+}
+
+fn spawn_barn(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let z = PLANE_Z_SIZE * BARN_SPREAD;
+    // the yard lies in front of the barn door, on the side facing the middle of the map
+    let yard = Vec3::new(0.0, terrain_height(0.0, z) + 0.1, z);
+    let barn = Vec3::new(0.0, BARN_SIZE.y / 2.0, BARN_SIZE.z / 2.0 + BARN_YARD_RADIUS);
+    let roof = Vec3::new(0.0, BARN_SIZE.y / 2.0 + 0.5, 0.0);
+
+    commands.spawn((
+        Name::new("Barn"),
+        Barn::default(),
+        StateScoped(Screen::InGame),
+        Mesh3d(meshes.add(Cylinder::new(BARN_YARD_RADIUS, 0.2))),
+        MeshMaterial3d(materials.add(StandardMaterial::from_color(ZINC_200))),
+        Transform::from_translation(yard),
+        children![(
+            RigidBody::Static,
+            Collider::cuboid(BARN_SIZE.x, BARN_SIZE.y, BARN_SIZE.z),
+            Mesh3d(meshes.add(Cuboid::from_size(BARN_SIZE))),
+            MeshMaterial3d(materials.add(StandardMaterial::from_color(RED_800))),
+            Transform::from_translation(barn),
+            children![(
+                Mesh3d(meshes.add(Cuboid::new(BARN_SIZE.x + 0.4, 1.0, BARN_SIZE.z + 0.4))),
+                MeshMaterial3d(materials.add(StandardMaterial::from_color(STONE_600))),
+                Transform::from_translation(roof),
+            )],
+        )],
+    ));
+}
+
+/// Unloads trailers parked in the barn yard
+fn deliver_cargo(
+    mut commands: Commands,
+    mut barns: Query<(&Transform, &mut Barn)>,
+    trailers: Query<&GlobalTransform, With<Trailer>>,
+    cargo: Query<(Entity, &Transform), With<Cargo>>,
+    mut currency: ResMut<Currency>,
+) {
+    for (barn_t, mut barn) in barns.iter_mut() {
+        for trailer_t in trailers.iter() {
+            let distance = trailer_t
+                .translation()
+                .xz()
+                .distance(barn_t.translation.xz());
+            if distance > BARN_YARD_RADIUS {
+                continue;
+            }
+
+            for (entity, transform) in cargo.iter() {
+                if !in_bed(trailer_t, transform.translation) {
+                    continue;
+                }
+
+                commands.entity(entity).despawn();
+                barn.delivered += 1;
+                if barn.delivered % FRUIT_PER_CURRENCY == 0 {
+                    currency.add(1);
+                }
+            }
+        }
+    }
+}
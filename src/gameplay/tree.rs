@@ -1,10 +1,18 @@
 use crate::PausableSystems;
 use crate::gameplay::DespawnAfter;
-use crate::gameplay::apple::{APPLE_RADIUS, AppleAssets, AppleSpawnEvent, AppleStrength};
+use crate::gameplay::apple::{APPLE_RADIUS, AppleSpawnEvent, AppleStrength};
+use crate::gameplay::apple_kind::AppleSpawnWeights;
+use crate::gameplay::boss::Boss;
+use crate::gameplay::fertility::Fertility;
 use crate::gameplay::health::*;
 use crate::gameplay::healthbars::healthbar;
 use crate::gameplay::level::{Ground, TERRAIN_HEIGHT};
 use crate::gameplay::saw::Sawable;
+use crate::gameplay::score::ScoreCounter;
+use crate::gameplay::steering::Obstacle;
+use crate::gameplay::tint::Tint;
+use crate::gameplay::tractor::Tractor;
+use crate::gameplay::tree_species::{TreeModel, TreeSpecies};
 use crate::screens::ingame::setup_gamescreen;
 use crate::{ReplaceOnHotreload, asset_tracking::LoadResource, screens::*};
 use avian3d::prelude::*;
@@ -17,16 +25,11 @@ use std::time::Duration;
 
 const TREE_STARTING_RADIUS: f32 = 0.5;
 pub const TREE_STARTING_HEIGHT: f32 = 3.0;
-const DEFAULT_APPLE_SPAWN_TIME_SEC: f32 = 5.0; // Time between apple spawns
 
-const RANDOM_SPAWN_X_MIN: f32 = -135.0;
-const RANDOM_SPAWN_X_MAX: f32 = 135.0;
-const RANDOM_SPAWN_Z_MIN: f32 = -135.0;
-const RANDOM_SPAWN_Z_MAX: f32 = 135.0;
 const RANDOM_SPAWN_REPEAT_TIME_SEC: u64 = 10;
-const TREE_HEALTH_INIT: u32 = 1;
-const TREE_HEALTH_INCREASE_TICK: f32 = 1.5;
 const MAXIMUM_TREES: usize = 50;
+/// Bullets barely scratch the bark
+const TREE_BULLET_DAMAGE_MULTIPLIER: f32 = 0.25;
 
 const DEFAULT_TREE_LOCATIONS: [Vec3; 3] = [
     vec3(22.0, 1000., 20.0),
@@ -36,8 +39,11 @@ const DEFAULT_TREE_LOCATIONS: [Vec3; 3] = [
 
 #[derive(Component, Reflect)]
 pub struct Tree {
+    pub species: TreeSpecies,
     pub apple_spawn_time_sec: f32,
     pub last_apple_spawn: f32,
+    /// apples of the current volley that still have to be thrown
+    pub pending_apples: usize,
     pub timer: Timer,
     // level progression
     pub level: u32,
@@ -46,20 +52,25 @@ pub struct Tree {
 impl Tree {
     const SCALE_PER_LEVEL: f32 = 0.5;
     const SCALE_DURATION_MS: u64 = 1500;
-    const LEVEL_UP_TIME: u64 = 10;
     const SCALE_SHAKE_DURATION_MS: u64 = 50;
     const SCALE_SHAKE_ANGLE_RADIAN: f32 = PI / 9.0;
     const SCALE_SHAKE_COUNT: u32 = 10;
 }
 
-fn calculate_max_health(tree_level: u32) -> u32 {
-    return 1 + (TREE_HEALTH_INCREASE_TICK * tree_level as f32) as u32;
+impl Tree {
+    fn scale(species: TreeSpecies, level: u32) -> Vec3 {
+        let scale = Tree::SCALE_PER_LEVEL + level as f32 * Tree::SCALE_PER_LEVEL;
+        Vec3::splat(scale * species.def().scale)
+    }
 }
 
 #[derive(Event)]
 pub struct TreeSpawnEvent {
     pub(crate) position: Vec3,
     pub(crate) startlevel: u32,
+    pub(crate) boss: bool,
+    /// picked by the progress of the run when not set
+    pub(crate) species: Option<TreeSpecies>,
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
@@ -68,6 +79,16 @@ pub struct TreeAssets {
     pub tree: Handle<Scene>,
     pub crown: Handle<Scene>,
     pub trunks: [Handle<Scene>; 3],
+    pub harmless: Handle<Scene>,
+}
+
+impl TreeAssets {
+    pub fn model(&self, model: TreeModel) -> Handle<Scene> {
+        match model {
+            TreeModel::Tree => self.tree.clone(),
+            TreeModel::Harmless => self.harmless.clone(),
+        }
+    }
 }
 
 #[derive(Component)]
@@ -84,6 +105,8 @@ impl FromWorld for TreeAssets {
                 assets.load("models/tree/tree.gltf#Scene3"),
                 assets.load("models/tree/tree.gltf#Scene4"),
             ],
+            harmless: assets
+                .load(GltfAssetLabel::Scene(0).from_asset("models/tree/harmless_tree.glb")),
         }
     }
 }
@@ -159,10 +182,11 @@ fn spawn_tree(
     mut raycast: MeshRayCast,
     ground: Query<Entity, With<Ground>>,
     trees: Query<&Tree>,
+    score: Res<ScoreCounter>,
 ) {
     for event in events.read() {
         let num_trees = trees.iter().len();
-        if num_trees >= MAXIMUM_TREES {
+        if num_trees >= MAXIMUM_TREES && !event.boss {
             continue;
         }
 
@@ -178,26 +202,38 @@ fn spawn_tree(
         );
 
         if let Some((_, hit)) = hits.first() {
+            let species = event
+                .species
+                .unwrap_or_else(|| TreeSpecies::pick(score.points));
+            let def = species.def();
+
             commands
                 .spawn((
-                    Name::new("Tree"),
+                    Name::new(format!("{} Tree", def.name)),
                     Tree {
-                        apple_spawn_time_sec: DEFAULT_APPLE_SPAWN_TIME_SEC,
+                        species,
+                        apple_spawn_time_sec: def.apple_spawn_time_sec,
                         last_apple_spawn: 0.0,
-                        timer: Timer::new(
-                            Duration::from_secs(Tree::LEVEL_UP_TIME),
-                            TimerMode::Repeating,
-                        ),
+                        pending_apples: 0,
+                        timer: Timer::new(def.growth_interval, TimerMode::Repeating),
                         level: event.startlevel,
                     },
-                    Health::new(calculate_max_health(event.startlevel)),
+                    species.apple_weights(event.startlevel),
+                    Health::new(species.max_health(event.startlevel)),
+                    // trees are rooted, bumping into them barely hurts
+                    Resistances::default()
+                        .with(DamageKind::Impact, 0.5)
+                        .with(DamageKind::Bullet, TREE_BULLET_DAMAGE_MULTIPLIER),
                     Sawable::default(),
                     AnchoredUiNodes::spawn_one(healthbar(100.)),
                     StateScoped(Screen::InGame),
                     ReplaceOnHotreload,
-                    SceneRoot(tree_assets.tree.clone()),
+                    SceneRoot(tree_assets.model(def.model)),
                     RigidBody::Static,
                     Collider::cylinder(TREE_STARTING_RADIUS, TREE_STARTING_HEIGHT * 2.0),
+                    Obstacle {
+                        radius: TREE_STARTING_RADIUS,
+                    },
                     Transform {
                         translation: hit.point,
                         scale: Vec3::splat(0.01),
@@ -205,9 +241,7 @@ fn spawn_tree(
                     },
                     Animator::new(level_up_animation(
                         Vec3::splat(0.01),
-                        Vec3::splat(
-                            Tree::SCALE_PER_LEVEL + event.startlevel as f32 * Tree::SCALE_PER_LEVEL,
-                        ),
+                        Tree::scale(species, event.startlevel),
                     )),
                     // children![
                     //     TreeApple,
@@ -219,6 +253,10 @@ fn spawn_tree(
                     //     SceneRoot(apple_assets.apple.clone()),
                     // ],
                 ))
+                .insert_if(Boss::default(), || event.boss)
+                .insert_if(Tint(def.tint.unwrap_or(Srgba::WHITE).into()), || {
+                    def.tint.is_some()
+                })
                 .observe(
                     |trigger: Trigger<Death>,
                      mut commands: Commands,
@@ -263,18 +301,21 @@ fn spawn_tree(
     }
 }
 
-fn spawn_tree_timer(mut commands: Commands, time: Res<Time>, mut config: ResMut<TreeSpawnConfig>) {
+fn spawn_tree_timer(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut config: ResMut<TreeSpawnConfig>,
+    fertility: Res<Fertility>,
+) {
     config.timer.tick(time.delta());
 
     if config.timer.finished() {
-        let x =
-            rand::random::<f32>() * (RANDOM_SPAWN_X_MAX - RANDOM_SPAWN_X_MIN) + RANDOM_SPAWN_X_MIN;
-        let z =
-            rand::random::<f32>() * (RANDOM_SPAWN_Z_MAX - RANDOM_SPAWN_Z_MIN) + RANDOM_SPAWN_Z_MIN;
-
+        // new trees grow where the forest is thick
         commands.send_event(TreeSpawnEvent {
-            position: Vec3::new(x, 1000., z),
+            position: fertility.sample_position(),
             startlevel: 0,
+            boss: false,
+            species: None,
         });
     }
 }
@@ -284,6 +325,8 @@ fn spawn_initial_trees(mut commands: Commands) {
         commands.send_event(TreeSpawnEvent {
             position: pos,
             startlevel: 1,
+            boss: false,
+            species: Some(TreeSpecies::Orchard),
         });
     }
 }
@@ -291,30 +334,42 @@ fn spawn_initial_trees(mut commands: Commands) {
 fn level_up_trees(
     mut commands: Commands,
     time: Res<Time>,
-    mut trees: Query<(Entity, &mut Health, &mut Tree, &Transform)>,
+    // bosses keep their size and health
+    mut trees: Query<
+        (
+            Entity,
+            &mut Health,
+            &mut Tree,
+            &mut AppleSpawnWeights,
+            &Transform,
+        ),
+        Without<Boss>,
+    >,
 ) {
-    for (ent, mut tree_health, mut tree, tree_t) in trees.iter_mut() {
+    for (ent, mut tree_health, mut tree, mut apple_weights, tree_t) in trees.iter_mut() {
         tree.timer.tick(time.delta());
 
         if tree.timer.just_finished() {
-            if tree_health.current == tree_health.max {
+            let max_level = tree.species.def().max_level;
+            if tree_health.current == tree_health.max && tree.level < max_level {
                 tree.level += 1;
-                tree_health.set_max_to(calculate_max_health(tree.level));
+                tree_health.set_max_to(tree.species.max_health(tree.level));
+                *apple_weights = tree.species.apple_weights(tree.level);
             }
 
             commands
                 .entity(ent)
                 .insert(Animator::new(level_up_animation(
                     tree_t.scale,
-                    Vec3::splat(Tree::SCALE_PER_LEVEL + tree.level as f32 * Tree::SCALE_PER_LEVEL),
+                    Tree::scale(tree.species, tree.level),
                 )));
         }
     }
 }
 
-fn shake_sawning_trees(
+fn shake_damaged_trees(
     mut commands: Commands,
-    mut event_reader: EventReader<DamageEvent>,
+    mut event_reader: EventReader<DamageDealt>,
     query: Query<Entity, With<Tree>>,
 ) {
     for event in event_reader.read() {
@@ -330,19 +385,41 @@ fn shake_sawning_trees(
 
 fn trees_spawn_apples(
     mut commands: Commands,
-    mut query: Query<(&mut Tree, &Transform)>,
+    mut query: Query<(&mut Tree, &AppleSpawnWeights, &Transform)>,
+    tractor: Query<&Transform, (With<Tractor>, Without<Tree>)>,
     time: Res<Time>,
 ) {
     let elapsed_time = time.elapsed_secs();
-    for (mut tree, tree_t) in query.iter_mut() {
-        if tree.level > 0 && elapsed_time > (tree.last_apple_spawn + tree.apple_spawn_time_sec) {
+    let tractor = tractor.single().ok().map(|transform| transform.translation);
+
+    for (mut tree, apple_weights, tree_t) in query.iter_mut() {
+        let def = tree.species.def();
+
+        if tree.level > 0
+            && tree.pending_apples == 0
+            && elapsed_time > (tree.last_apple_spawn + tree.apple_spawn_time_sec)
+        {
+            tree.pending_apples = def.volley;
             tree.last_apple_spawn = elapsed_time;
+        }
+
+        let interval = def.pattern.shot_interval_sec();
+        while tree.pending_apples > 0 {
+            let index = def.volley - tree.pending_apples;
+            if elapsed_time < tree.last_apple_spawn + index as f32 * interval {
+                break;
+            }
+            tree.pending_apples -= 1;
+
             let spawn_pos = tree_t.translation
                 + (Vec3::Y * TREE_STARTING_HEIGHT * tree_t.scale.y + APPLE_RADIUS * 2.0);
+            let (offset, velocity) = def.pattern.launch(index, def.volley, spawn_pos, tractor);
 
             commands.send_event(AppleSpawnEvent {
-                at: spawn_pos,
+                at: spawn_pos + offset * APPLE_RADIUS * 2.0,
                 apple_strength: AppleStrength::from_tree_level(tree.level),
+                kind: apple_weights.pick(),
+                velocity,
             });
         }
     }
@@ -364,7 +441,7 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         PostUpdate,
-        (shake_sawning_trees
+        (shake_damaged_trees
             .run_if(in_state(Screen::InGame))
             .in_set(PausableSystems),),
     );
@@ -377,13 +454,6 @@ pub(super) fn plugin(app: &mut App) {
             .after(setup_gamescreen)
             .run_if(in_state(Screen::InGame)),
     );
-    #[cfg(feature = "dev")]
-    app.add_systems(
-        Update,
-        spawn_tree
-            .after(setup_gamescreen)
-            .run_if(in_state(Screen::TractorBuild)),
-    );
 
     app.add_systems(
         Update,
@@ -0,0 +1,219 @@
+use crate::gameplay::apple_kind::{AppleKind, AppleSpawnWeights};
+use bevy::color::palettes::tailwind::{LIME_300, ORANGE_200, STONE_400};
+
+use super::*;
+
+/// How long the apples of a volley take to land when lobbed
+const LOB_FLIGHT_SEC: f32 = 1.6;
+const GRAVITY: f32 = 9.81;
+const BURST_SPEED: f32 = 9.0;
+const SPIRAL_SPEED: f32 = 11.0;
+const SPIRAL_SHOT_INTERVAL_SEC: f32 = 0.12;
+/// Points at which the later species have fully grown in
+const LATE_GAME_POINTS: f32 = 300.0;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<TreeSpecies>();
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum TreeSpecies {
+    /// the plain apple tree
+    #[default]
+    Orchard,
+    /// grows fast and dies fast, throws splitters
+    Crabapple,
+    /// slow and tough, lobs armored apples
+    Oak,
+    /// spins apples around itself
+    Willow,
+}
+
+/// How a tree throws the apples of a volley
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum VolleyPattern {
+    /// all at once in a ring around the tree, a lone apple jumps at the tractor
+    Burst,
+    /// one after the other all around the tree
+    Spiral,
+    /// high arcs that land where the tractor is
+    Lobbed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeModel {
+    Tree,
+    Harmless,
+}
+
+pub struct SpeciesDef {
+    pub name: &'static str,
+    /// time between level ups
+    pub growth_interval: Duration,
+    pub base_health: u32,
+    pub health_per_level: f32,
+    pub max_level: u32,
+    pub apple_spawn_time_sec: f32,
+    /// multiplies the default apple weights of the tree's level
+    pub apple_mix: &'static [(AppleKind, f32)],
+    pub volley: usize,
+    pub pattern: VolleyPattern,
+    pub model: TreeModel,
+    pub tint: Option<Srgba>,
+    pub scale: f32,
+}
+
+const ORCHARD: SpeciesDef = SpeciesDef {
+    name: "Orchard",
+    growth_interval: Duration::from_secs(10),
+    base_health: 1,
+    health_per_level: 1.5,
+    max_level: 10,
+    apple_spawn_time_sec: 5.0,
+    apple_mix: &[],
+    volley: 1,
+    pattern: VolleyPattern::Burst,
+    model: TreeModel::Tree,
+    tint: None,
+    scale: 1.0,
+};
+
+const CRABAPPLE: SpeciesDef = SpeciesDef {
+    name: "Crabapple",
+    growth_interval: Duration::from_secs(6),
+    base_health: 1,
+    health_per_level: 1.0,
+    max_level: 5,
+    apple_spawn_time_sec: 4.0,
+    apple_mix: &[(AppleKind::Splitter, 3.0), (AppleKind::Armored, 0.0)],
+    volley: 3,
+    pattern: VolleyPattern::Burst,
+    model: TreeModel::Tree,
+    tint: Some(ORANGE_200),
+    scale: 0.8,
+};
+
+const OAK: SpeciesDef = SpeciesDef {
+    name: "Oak",
+    growth_interval: Duration::from_secs(20),
+    base_health: 4,
+    health_per_level: 3.0,
+    max_level: 8,
+    apple_spawn_time_sec: 8.0,
+    apple_mix: &[(AppleKind::Armored, 3.0), (AppleKind::Rotten, 1.5)],
+    volley: 2,
+    pattern: VolleyPattern::Lobbed,
+    model: TreeModel::Harmless,
+    tint: Some(STONE_400),
+    scale: 1.2,
+};
+
+const WILLOW: SpeciesDef = SpeciesDef {
+    name: "Willow",
+    growth_interval: Duration::from_secs(12),
+    base_health: 2,
+    health_per_level: 2.0,
+    max_level: 8,
+    apple_spawn_time_sec: 9.0,
+    apple_mix: &[(AppleKind::Bouncer, 3.0), (AppleKind::Golden, 1.5)],
+    volley: 8,
+    pattern: VolleyPattern::Spiral,
+    model: TreeModel::Tree,
+    tint: Some(LIME_300),
+    scale: 1.0,
+};
+
+impl TreeSpecies {
+    pub const ALL: [TreeSpecies; 4] = [
+        TreeSpecies::Orchard,
+        TreeSpecies::Crabapple,
+        TreeSpecies::Oak,
+        TreeSpecies::Willow,
+    ];
+
+    pub fn def(self) -> &'static SpeciesDef {
+        match self {
+            TreeSpecies::Orchard => &ORCHARD,
+            TreeSpecies::Crabapple => &CRABAPPLE,
+            TreeSpecies::Oak => &OAK,
+            TreeSpecies::Willow => &WILLOW,
+        }
+    }
+
+    pub fn max_health(self, level: u32) -> u32 {
+        let def = self.def();
+        def.base_health + (def.health_per_level * level as f32) as u32
+    }
+
+    pub fn apple_weights(self, level: u32) -> AppleSpawnWeights {
+        AppleSpawnWeights::for_level(level).scaled(self.def().apple_mix)
+    }
+
+    /// The first trees are all orchards, the other species show up as the score goes up
+    fn weight(self, points: usize) -> f32 {
+        let progress = (points as f32 / LATE_GAME_POINTS).min(1.0);
+        match self {
+            TreeSpecies::Orchard => 10.0 - 6.0 * progress,
+            TreeSpecies::Crabapple => 4.0 * progress,
+            TreeSpecies::Oak => 3.0 * progress * progress,
+            TreeSpecies::Willow => 3.0 * progress,
+        }
+    }
+
+    pub fn pick(points: usize) -> TreeSpecies {
+        let total: f32 = Self::ALL.iter().map(|species| species.weight(points)).sum();
+        let mut roll = rand::random::<f32>() * total;
+
+        for species in Self::ALL {
+            roll -= species.weight(points);
+            if roll <= 0.0 {
+                return species;
+            }
+        }
+
+        TreeSpecies::Orchard
+    }
+}
+
+impl VolleyPattern {
+    /// Time between two apples of the same volley
+    pub fn shot_interval_sec(self) -> f32 {
+        match self {
+            VolleyPattern::Spiral => SPIRAL_SHOT_INTERVAL_SEC,
+            VolleyPattern::Burst | VolleyPattern::Lobbed => 0.0,
+        }
+    }
+
+    /// Where the apple starts relative to the tree top and how fast it goes,
+    /// `None` lets the apple jump at the tractor by itself
+    pub fn launch(
+        self,
+        index: usize,
+        count: usize,
+        from: Vec3,
+        tractor: Option<Vec3>,
+    ) -> (Vec3, Option<Vec3>) {
+        let angle = index as f32 / count.max(1) as f32 * 2.0 * PI;
+        let around = Vec3::new(angle.cos(), 0.0, angle.sin());
+
+        match self {
+            VolleyPattern::Burst if count <= 1 => (Vec3::ZERO, None),
+            VolleyPattern::Burst => {
+                let spread = (around + Vec3::Y * 2.0).normalize();
+                (around, Some(spread * BURST_SPEED))
+            }
+            VolleyPattern::Spiral => (around, Some((around + Vec3::Y).normalize() * SPIRAL_SPEED)),
+            VolleyPattern::Lobbed => {
+                let Some(target) = tractor else {
+                    return (around, None);
+                };
+                // spread the landing spots a little around the tractor
+                let target = target + around * index as f32;
+                let velocity =
+                    (target - from) / LOB_FLIGHT_SEC + Vec3::Y * 0.5 * GRAVITY * LOB_FLIGHT_SEC;
+                // apples start apart so they don't push each other off their arcs
+                (around, Some(velocity))
+            }
+        }
+    }
+}
@@ -1,16 +1,51 @@
 use super::*;
+use crate::audio::sound_effect_with;
+use crate::gameplay::parts::PartHealth;
+use crate::theme::palette::LABEL_TEXT;
+use bevy::color::palettes::tailwind::{GREEN_400, SKY_400};
 use bevy_tweening::lens::TransformPositionLens;
 use bevy_tweening::{Animator, RepeatStrategy, Tween};
+use bevy_ui_anchor::{AnchorUiConfig, AnchorUiNode, AnchoredUiNodes};
 use std::time::Duration;
 
 pub const BARREL_LEN: f32 = 2.0;
 pub const BARREL_RADIE: f32 = 0.2;
 pub const BODY_RADIE: f32 = 0.5;
+pub const TURRET_CRIT_CHANCE: f32 = 0.1;
+pub const TURRET_CRIT_MULTIPLIER: f32 = 2.0;
+pub const TURRET_MAGAZINE_SIZE: u32 = 12;
+pub const TURRET_RELOAD_TIME: Duration = Duration::from_millis(1500);
+/// Part of the reload where reloading again finishes it right away
+const ACTIVE_RELOAD_WINDOW: (f32, f32) = (0.45, 0.65);
 
 pub fn turret_plugin(app: &mut App) {
     app.register_type::<Turret>();
     app.register_type::<TurretDamage>();
-    app.add_systems(Update, tick_and_fire_turret);
+    app.register_type::<TurretMagazine>();
+    app.load_resource::<TurretAssets>();
+    app.add_systems(
+        Update,
+        (
+            tick_and_fire_turret,
+            update_ammo_gauges,
+            despawn_orphaned_ammo_gauges,
+        ),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct TurretAssets {
+    empty_sound: Handle<AudioSource>,
+}
+
+impl FromWorld for TurretAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets: &AssetServer = world.resource::<AssetServer>();
+        Self {
+            empty_sound: assets.load::<AudioSource>("audio/sound_effects/button_click.ogg"),
+        }
+    }
 }
 
 #[derive(Component, Reflect)]
@@ -19,6 +54,73 @@ pub struct Turret {
     pub firing: bool,
 }
 
+/// Optional ammo for a turret, a turret without one never runs dry
+#[derive(Component, Reflect)]
+pub struct TurretMagazine {
+    pub capacity: u32,
+    pub rounds: u32,
+    pub reload: Timer,
+    pub reloading: bool,
+    /// missed the active reload window, no second try during this reload
+    fumbled: bool,
+}
+
+impl TurretMagazine {
+    pub fn new(capacity: u32, reload_time: Duration) -> Self {
+        Self {
+            capacity,
+            rounds: capacity,
+            reload: Timer::new(reload_time, TimerMode::Once),
+            reloading: false,
+            fumbled: false,
+        }
+    }
+
+    pub fn start_reload(&mut self) {
+        if !self.reloading && self.rounds < self.capacity {
+            self.reloading = true;
+            self.fumbled = false;
+            self.reload.reset();
+        }
+    }
+
+    fn finish_reload(&mut self) {
+        self.reloading = false;
+        self.rounds = self.capacity;
+    }
+
+    pub fn in_active_window(&self) -> bool {
+        let progress = self.reload.fraction();
+        self.reloading
+            && !self.fumbled
+            && progress >= ACTIVE_RELOAD_WINDOW.0
+            && progress <= ACTIVE_RELOAD_WINDOW.1
+    }
+
+    /// Reloading during the active window finishes the reload, missing it means waiting it out
+    pub fn active_reload(&mut self) {
+        if self.in_active_window() {
+            self.finish_reload();
+        } else {
+            self.fumbled = true;
+        }
+    }
+
+    /// how full the gauge is, reload progress while reloading
+    pub fn fraction(&self) -> f32 {
+        if self.reloading {
+            self.reload.fraction()
+        } else {
+            self.rounds as f32 / self.capacity as f32
+        }
+    }
+}
+
+#[derive(Component)]
+struct AmmoGauge;
+#[derive(Component)]
+struct AmmoGaugeFill;
+
 #[derive(Component, Reflect)]
 pub struct TurretDamage(pub u32);
 impl Default for TurretDamage {
@@ -28,6 +130,7 @@ impl Default for TurretDamage {
 }
 
 use crate::gameplay::bullet::BulletSpawnEvent;
+use crate::gameplay::health::Critical;
 
 #[cfg_attr(feature = "dev_native", hot)]
 fn tick_and_fire_turret(
@@ -39,14 +142,52 @@ fn tick_and_fire_turret(
         &mut Turret,
         &GlobalTransform,
         &TurretDamage,
+        &Critical,
+        Option<&mut TurretMagazine>,
+        Option<&PartHealth>,
     )>,
     mut fire_bullet_evt: EventWriter<BulletSpawnEvent>,
+    assets: Res<TurretAssets>,
 ) {
     use crate::gameplay::bullet::Bullet;
 
-    for (entity, local_transform, mut turret, transform, turret_damage) in turrets.iter_mut() {
-        turret.rate_of_fire.tick(time.delta());
+    for (
+        entity,
+        local_transform,
+        mut turret,
+        transform,
+        turret_damage,
+        critical,
+        mut magazine,
+        condition,
+    ) in turrets.iter_mut()
+    {
+        // damaged turrets fire slower
+        let efficiency = condition.map_or(1.0, PartHealth::efficiency);
+        turret.rate_of_fire.tick(time.delta().mul_f32(efficiency));
+
+        if let Some(magazine) = magazine.as_mut() {
+            if magazine.reloading {
+                magazine.reload.tick(time.delta());
+                if magazine.reload.finished() {
+                    magazine.finish_reload();
+                }
+            }
+        }
+
         if turret.rate_of_fire.finished() && turret.firing {
+            if let Some(magazine) = magazine.as_mut() {
+                if magazine.reloading {
+                    continue;
+                }
+
+                magazine.rounds = magazine.rounds.saturating_sub(1);
+                if magazine.rounds == 0 {
+                    commands.spawn(sound_effect_with(assets.empty_sound.clone(), 1.0, 0.5));
+                    magazine.start_reload();
+                }
+            }
+
             if let Ok(mut entity) = commands.get_entity(entity) {
                 entity.insert(Animator::new(
                     Tween::new(
@@ -70,7 +211,9 @@ fn tick_and_fire_turret(
                 at: bullet_spawnpoint,
                 dir: forward,
                 speed: bullet::BULLET_SPEED,
-                bullet: Bullet::new(turret_damage.0, 1.0),
+                bullet: Bullet::new(turret_damage.0, 1.0).with_critical(*critical),
+                owner: Some(entity),
+                ignore: Vec::new(),
             });
         }
     }
@@ -93,6 +236,9 @@ pub fn turret(
         MeshMaterial3d(materials.add(StandardMaterial::from_color(BLACK))),
         Transform::from_translation(pos),
         TurretDamage::default(),
+        Critical::new(TURRET_CRIT_CHANCE, TURRET_CRIT_MULTIPLIER),
+        TurretMagazine::new(TURRET_MAGAZINE_SIZE, TURRET_RELOAD_TIME),
+        AnchoredUiNodes::spawn_one(ammo_gauge()),
         children![(
             Transform::from_rotation(Quat::from_rotation_x(-90f32.to_radians())),
             children![(
@@ -105,3 +251,65 @@ pub fn turret(
         )],
     )
 }
+
+fn ammo_gauge() -> impl Bundle {
+    (
+        Name::new("AmmoGauge"),
+        AmmoGauge,
+        Node {
+            width: Val::Px(40.),
+            height: Val::Px(4.),
+            ..Default::default()
+        },
+        BackgroundColor(BLACK.with_alpha(0.5).into()),
+        Outline::new(Val::Px(1.), Val::Px(0.), WHITE.into()),
+        AnchorUiConfig {
+            offset: Some(Vec3::Y * 1.5),
+            ..Default::default()
+        },
+        Children::spawn_one((
+            Name::new("AmmoGaugeFill"),
+            AmmoGaugeFill,
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..Default::default()
+            },
+            BackgroundColor(LABEL_TEXT),
+        )),
+    )
+}
+
+fn update_ammo_gauges(
+    turrets: Query<(&TurretMagazine, &AnchoredUiNodes)>,
+    gauges: Query<&Children, With<AmmoGauge>>,
+    mut fills: Query<(&mut Node, &mut BackgroundColor), With<AmmoGaugeFill>>,
+) {
+    for (magazine, ui_nodes) in turrets.iter() {
+        let color: Color = if magazine.in_active_window() {
+            GREEN_400.into()
+        } else if magazine.reloading {
+            SKY_400.into()
+        } else {
+            LABEL_TEXT
+        };
+
+        for children in gauges.iter_many(ui_nodes.collection()) {
+            for child in children.collection() {
+                if let Ok((mut node, mut background)) = fills.get_mut(*child) {
+                    node.width = Val::Percent(magazine.fraction() * 100.);
+                    background.0 = color;
+                }
+            }
+        }
+    }
+}
+
+fn despawn_orphaned_ammo_gauges(
+    mut commands: Commands,
+    gauges: Query<Entity, (With<AmmoGauge>, Without<AnchorUiNode>)>,
+) {
+    for gauge in gauges.iter() {
+        commands.entity(gauge).despawn();
+    }
+}
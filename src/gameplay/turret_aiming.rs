@@ -1,18 +1,33 @@
+use bevy::window::CursorMoved;
 use bevy_mod_lookat::RotateTo;
 
 use crate::{
     PausableSystems,
-    gameplay::{apple::Apple, bullet::BULLET_SPEED, tractor::Tractor, turret::Turret},
+    gameplay::{
+        apple::Apple, bullet::BULLET_SPEED, level::terrain_height, tractor::Tractor, turret::Turret,
+    },
 };
 
 use super::*;
 
 const AIM_RADIE: f32 = 20.;
+/// The right stick has to be pushed this far before it takes over from the mouse
+const STICK_DEADZONE: f32 = 0.2;
+pub const MIN_STICK_AIM_RADIUS: f32 = 5.0;
+pub const MAX_STICK_AIM_RADIUS: f32 = 40.0;
+pub const MIN_STICK_SENSITIVITY: f32 = 0.25;
+pub const MAX_STICK_SENSITIVITY: f32 = 3.0;
 
 pub fn plugin(app: &mut App) {
+    app.init_resource::<AimMode>();
+    app.init_resource::<StickAim>();
+    app.init_resource::<StickAimSettings>();
+
     app.add_systems(
         Update,
-        (move_sight, aim_all_turrets_to_sight).in_set(PausableSystems),
+        (switch_aim_mode, move_sight, aim_all_turrets_to_sight)
+            .chain()
+            .in_set(PausableSystems),
     );
 }
 
@@ -23,6 +38,60 @@ pub fn sight() -> impl Bundle {
     (Sight, Transform::from_translation(Vec3::X * 1000.))
 }
 
+/// What moves the sight, whichever was touched last
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AimMode {
+    #[default]
+    Mouse,
+    Gamepad,
+}
+
+/// How the right stick moves the sight around the tractor
+#[derive(Resource, Debug)]
+pub struct StickAimSettings {
+    /// how far from the tractor the sight can go
+    pub radius: f32,
+    /// radii per second at full tilt
+    pub sensitivity: f32,
+}
+
+impl Default for StickAimSettings {
+    fn default() -> Self {
+        Self {
+            radius: 20.0,
+            sensitivity: 1.5,
+        }
+    }
+}
+
+/// Where the stick has put the sight, relative to the tractor
+#[derive(Resource, Debug, Default)]
+struct StickAim(Vec2);
+
+fn switch_aim_mode(
+    mut mode: ResMut<AimMode>,
+    mut stick_aim: ResMut<StickAim>,
+    mut cursor: EventReader<CursorMoved>,
+    gamepads: Query<&Gamepad>,
+    sight: Single<&Transform, With<Sight>>,
+    tractor: Single<&Transform, (With<Tractor>, Without<Sight>)>,
+    settings: Res<StickAimSettings>,
+) {
+    if cursor.read().count() > 0 {
+        mode.set_if_neq(AimMode::Mouse);
+    }
+
+    let stick_moved = gamepads
+        .iter()
+        .any(|gamepad| gamepad.right_stick().length() > STICK_DEADZONE);
+    if stick_moved && *mode == AimMode::Mouse {
+        // pick up from wherever the mouse left the sight
+        let offset = (sight.translation - tractor.translation).xz();
+        stick_aim.0 = offset.clamp_length_max(settings.radius);
+        *mode = AimMode::Gamepad;
+    }
+}
+
 fn aim_all_turrets_to_sight(
     mut commands: Commands,
     sight: Single<Entity, With<Sight>>,
@@ -44,42 +113,72 @@ fn move_sight(
     windows: Query<&Window>,
     apples: Query<(&Transform, &LinearVelocity), (With<Apple>, Without<Sight>)>,
     tractor: Single<&Transform, (With<Tractor>, Without<Apple>, Without<Sight>)>,
+    mode: Res<AimMode>,
+    mut stick_aim: ResMut<StickAim>,
+    settings: Res<StickAimSettings>,
+    gamepads: Query<&Gamepad>,
+    time: Res<Time>,
     mut gizmos: Gizmos,
 ) {
-    let Ok(windows) = windows.single() else {
-        return;
-    };
-
     let (camera, camera_transform) = *camera_query;
 
-    let Some(cursor_position) = windows.cursor_position() else {
-        return;
-    };
-
-    // Calculate a ray pointing from the camera into the world based on the cursor's position.
-    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else {
-        return;
-    };
-
-    // Calculate if and where the ray is hitting the ground plane.
-    let hits = raycast.cast_ray(ray, &MeshRayCastSettings::default().always_early_exit());
-
-    let Some((_e, hit)) = hits.first() else {
-        return;
+    let aim_point = match *mode {
+        AimMode::Mouse => {
+            let Ok(windows) = windows.single() else {
+                return;
+            };
+
+            let Some(cursor_position) = windows.cursor_position() else {
+                return;
+            };
+
+            // Calculate a ray pointing from the camera into the world based on the cursor's position.
+            let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else {
+                return;
+            };
+
+            // Calculate if and where the ray is hitting the ground plane.
+            let hits = raycast.cast_ray(ray, &MeshRayCastSettings::default().always_early_exit());
+
+            let Some((_e, hit)) = hits.first() else {
+                return;
+            };
+            hit.point
+        }
+        AimMode::Gamepad => {
+            let stick = gamepads
+                .iter()
+                .map(Gamepad::right_stick)
+                .find(|stick| stick.length() > STICK_DEADZONE)
+                .unwrap_or_default();
+
+            // pushing the stick up moves the sight up the screen
+            let forward = camera_transform.forward().xz().normalize_or(Vec2::NEG_Y);
+            let right = forward.perp();
+            let step = (right * stick.x + forward * stick.y)
+                * settings.sensitivity
+                * settings.radius
+                * time.delta_secs();
+            stick_aim.0 = (stick_aim.0 + step).clamp_length_max(settings.radius);
+
+            let x = tractor.translation.x + stick_aim.0.x;
+            let z = tractor.translation.z + stick_aim.0.y;
+            Vec3::new(x, terrain_height(x, z), z)
+        }
     };
 
     let target = if let Some((apple_t, apple_v)) = apples
         .iter()
-        .filter(|(t, _v)| t.translation.distance_squared(hit.point) < AIM_RADIE.powi(2))
+        .filter(|(t, _v)| t.translation.distance_squared(aim_point) < AIM_RADIE.powi(2))
         .min_by(|(t1, _v1), (t2, _v2)| {
             t1.translation
-                .distance_squared(hit.point)
-                .total_cmp(&t2.translation.distance_squared(hit.point))
+                .distance_squared(aim_point)
+                .total_cmp(&t2.translation.distance_squared(aim_point))
         }) {
         apple_t.translation
             + apple_v.0 * (apple_t.translation.distance(tractor.translation) / BULLET_SPEED)
     } else {
-        hit.point + ground.up() * 0.4
+        aim_point + ground.up() * 0.4
     };
 
     sight.translation = target;
@@ -0,0 +1,192 @@
+use crate::Pause;
+use crate::gameplay::fuel::Fuel;
+use crate::gameplay::parts::PartHealth;
+use crate::gameplay::tractor::{LeftWheels, RightWheels, Wheel};
+use crate::gameplay::vehicle_kind::VehicleKind;
+
+use super::*;
+
+/// Brake torque fades in below this wheel surface speed, so stopped wheels don't jitter
+const BRAKE_FADE_SPEED: f32 = 1.0;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<VehicleControls>();
+    app.register_type::<VehicleTuning>();
+
+    // forces are applied per physics step, so they don't depend on the frame rate.
+    // physics stands still while paused, so forces must not pile up in the meantime
+    app.add_systems(
+        FixedUpdate,
+        (apply_suspension, drive_wheels).run_if(in_state(Pause(false))),
+    );
+}
+
+/// What the driver currently asks the vehicle to do, written by the input
+#[derive(Component, Debug, Default, Clone, Reflect)]
+pub struct VehicleControls {
+    /// -1 full reverse to 1 full forward
+    pub throttle: f32,
+    /// -1 left to 1 right, spins the sides against each other
+    pub steer: f32,
+    /// slows the wheels down gently
+    pub brake: bool,
+    /// locks the wheels
+    pub handbrake: bool,
+}
+
+impl VehicleControls {
+    /// nobody is driving, the vehicle parks with locked wheels so it doesn't roll away
+    pub fn idle(&self) -> bool {
+        self.throttle == 0.0 && self.steer == 0.0 && !self.brake
+    }
+}
+
+/// How the vehicle's wheels drive, brake and spring
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct VehicleTuning {
+    /// torque on each wheel at full throttle
+    pub motor_torque: f32,
+    /// torque against the spin of each wheel when braking
+    pub brake_torque: f32,
+    /// surface speed above which the motor stops pushing
+    pub max_wheel_speed: f32,
+    /// friction of the wheels on the ground
+    pub traction: f32,
+    pub suspension: Suspension,
+}
+
+impl Default for VehicleTuning {
+    fn default() -> Self {
+        Self {
+            motor_torque: 6.0,
+            brake_torque: 8.0,
+            max_wheel_speed: 15.0,
+            traction: 0.8,
+            suspension: Suspension::default(),
+        }
+    }
+}
+
+/// Spring and damper between the body and a wheel hub
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+pub struct Suspension {
+    pub stiffness: f32,
+    pub damping: f32,
+    /// how far the hub can move up or down from its rest position
+    pub travel: f32,
+}
+
+impl Default for Suspension {
+    fn default() -> Self {
+        Self {
+            stiffness: 160.0,
+            damping: 20.0,
+            travel: 0.25,
+        }
+    }
+}
+
+/// Sits between the body and a wheel, slides up and down on a prismatic joint
+#[derive(Component, Debug)]
+pub struct WheelHub {
+    pub vehicle: Entity,
+    /// where the hub rests, relative to the body
+    pub anchor: Vec3,
+}
+
+fn apply_suspension(
+    mut hubs: Query<(
+        &WheelHub,
+        &Suspension,
+        &Transform,
+        &LinearVelocity,
+        &mut ExternalForce,
+    )>,
+    mut bodies: Query<
+        (
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+            &ComputedCenterOfMass,
+            &mut ExternalForce,
+        ),
+        Without<WheelHub>,
+    >,
+) {
+    for (hub, suspension, hub_t, hub_v, mut hub_force) in hubs.iter_mut() {
+        let Ok((body_t, body_v, body_w, center_of_mass, mut body_force)) =
+            bodies.get_mut(hub.vehicle)
+        else {
+            continue;
+        };
+
+        let up = body_t.up().as_vec3();
+        let anchor = body_t.transform_point(hub.anchor);
+        let anchor_v = body_v.0 + body_w.0.cross(anchor - body_t.translation);
+
+        let compression = (hub_t.translation - anchor).dot(up);
+        let speed = (hub_v.0 - anchor_v).dot(up);
+        let spring = -(suspension.stiffness * compression + suspension.damping * speed) * up;
+
+        hub_force.set_force(spring);
+        body_force.apply_force_at_point(-spring, anchor, body_t.transform_point(center_of_mass.0));
+    }
+}
+
+/// Skid steering: each side gets the throttle plus or minus the steering
+fn drive_wheels(
+    vehicles: Query<(
+        &Transform,
+        &VehicleKind,
+        &VehicleControls,
+        &VehicleTuning,
+        Option<&Fuel>,
+        Option<&PartHealth>,
+        &LeftWheels,
+        &RightWheels,
+    )>,
+    mut wheels: Query<(&mut AngularVelocity, &mut ExternalTorque), With<Wheel>>,
+) {
+    for (transform, kind, controls, tuning, fuel, engine, left, right) in vehicles.iter() {
+        let radius = kind.spec().wheel_radius;
+        let motor_torque = tuning.motor_torque
+            * fuel.map_or(1.0, Fuel::torque_factor)
+            * engine.map_or(1.0, PartHealth::efficiency);
+        // a wheel rolling forward spins around the body's left axis
+        let axle = transform.left().as_vec3();
+        let sides = [
+            (controls.throttle + controls.steer, left.collection()),
+            (controls.throttle - controls.steer, right.collection()),
+        ];
+
+        for (drive, side) in sides {
+            let drive = drive.clamp(-1.0, 1.0);
+
+            for wheel in side.iter() {
+                let Ok((mut spin, mut torque)) = wheels.get_mut(*wheel) else {
+                    continue;
+                };
+                let surface_speed = spin.dot(axle) * radius;
+
+                if controls.handbrake || controls.idle() {
+                    spin.0 -= axle * spin.dot(axle);
+                    torque.clear();
+                    continue;
+                }
+
+                // driving against the roll brakes first, like a car
+                let braking = controls.brake || drive * surface_speed < 0.0;
+                let amount = if braking {
+                    let fade = (surface_speed.abs() / BRAKE_FADE_SPEED).min(1.0);
+                    -surface_speed.signum() * tuning.brake_torque * fade
+                } else if surface_speed.abs() < tuning.max_wheel_speed {
+                    drive * motor_torque
+                } else {
+                    0.0
+                };
+
+                torque.set_torque(axle * amount);
+            }
+        }
+    }
+}
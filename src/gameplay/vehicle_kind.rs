@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::gameplay::ability::Ability;
+
+use super::*;
+
+/// Extra space between the body and the wheels on each side
+const WHEEL_GAP: f32 = 0.2;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<VehicleKind>();
+}
+
+/// The vehicles the player can drive
+#[derive(
+    Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect,
+)]
+pub enum VehicleKind {
+    #[default]
+    Tractor,
+    /// small, quick and fragile
+    QuadBike,
+    /// wide, slow and very tough
+    Combine,
+    /// long with three axles
+    Truck,
+}
+
+/// Everything that makes up a vehicle
+#[derive(Debug)]
+pub struct VehicleSpec {
+    pub name: &'static str,
+    /// width, height and length of the body, wheels included in the length
+    pub size: Vec3,
+    pub wheel_radius: f32,
+    /// where the axles sit along the body, front is negative
+    pub axles: &'static [f32],
+    /// density of the body collider
+    pub density: f32,
+    pub health: u32,
+    pub top_speed: f32,
+    /// size of the fuel tank
+    pub fuel: f32,
+    /// stretches the tractor model to the body, in vehicle axes
+    pub model_scale: Vec3,
+    pub ability: Ability,
+}
+
+const TRACTOR: VehicleSpec = VehicleSpec {
+    name: "Tractor",
+    size: Vec3::new(1.0, 2.0, 4.0),
+    wheel_radius: 0.4,
+    axles: &[-1.4, 1.4],
+    density: 1.0,
+    health: 5,
+    top_speed: 15.0,
+    fuel: 100.0,
+    model_scale: Vec3::ONE,
+    ability: Ability::Boost,
+};
+
+const QUAD_BIKE: VehicleSpec = VehicleSpec {
+    name: "Quad bike",
+    size: Vec3::new(0.8, 1.0, 2.4),
+    wheel_radius: 0.35,
+    axles: &[-0.75, 0.75],
+    density: 0.8,
+    health: 3,
+    top_speed: 20.0,
+    fuel: 60.0,
+    model_scale: Vec3::new(0.8, 0.5, 0.6),
+    ability: Ability::Horn,
+};
+
+const COMBINE: VehicleSpec = VehicleSpec {
+    name: "Combine",
+    size: Vec3::new(2.0, 2.6, 5.0),
+    wheel_radius: 0.6,
+    axles: &[-1.6, 1.7],
+    density: 1.2,
+    health: 9,
+    top_speed: 10.0,
+    fuel: 140.0,
+    model_scale: Vec3::new(2.0, 1.3, 1.25),
+    ability: Ability::Shockwave,
+};
+
+const TRUCK: VehicleSpec = VehicleSpec {
+    name: "Truck",
+    size: Vec3::new(1.4, 1.8, 5.2),
+    wheel_radius: 0.45,
+    axles: &[-2.0, 1.0, 2.0],
+    density: 1.1,
+    health: 7,
+    top_speed: 14.0,
+    fuel: 160.0,
+    model_scale: Vec3::new(1.4, 0.9, 1.3),
+    ability: Ability::Shield,
+};
+
+impl VehicleKind {
+    pub const ALL: [VehicleKind; 4] = [
+        VehicleKind::Tractor,
+        VehicleKind::QuadBike,
+        VehicleKind::Combine,
+        VehicleKind::Truck,
+    ];
+
+    pub fn spec(self) -> &'static VehicleSpec {
+        match self {
+            VehicleKind::Tractor => &TRACTOR,
+            VehicleKind::QuadBike => &QUAD_BIKE,
+            VehicleKind::Combine => &COMBINE,
+            VehicleKind::Truck => &TRUCK,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+}
+
+impl VehicleSpec {
+    /// size of the body collider, the wheels stick out in front and behind
+    pub fn body_size(&self) -> Vec3 {
+        self.size - Vec3::Z * self.wheel_radius * 2.0
+    }
+
+    /// the top of the body, relative to its center
+    pub fn top(&self) -> f32 {
+        self.size.y / 2.0
+    }
+
+    /// where the wheels sit on the left side, the right side is mirrored
+    pub fn left_wheels(&self) -> impl Iterator<Item = Vec3> + '_ {
+        let x = -(self.size.x / 2.0 + WHEEL_GAP + self.wheel_radius);
+        let y = -self.size.y / 2.0 + self.wheel_radius / 2.0 + 0.1;
+        self.axles.iter().map(move |z| Vec3::new(x, y, *z))
+    }
+
+    pub fn wheel_count(&self) -> usize {
+        self.axles.len() * 2
+    }
+
+    /// how far out the wheels reach, measured from the middle
+    pub fn half_track(&self) -> f32 {
+        self.size.x / 2.0 + WHEEL_GAP + self.wheel_radius * 2.0
+    }
+}
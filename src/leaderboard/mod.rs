@@ -1,4 +1,5 @@
 use crate::gameplay::Val::Px;
+use crate::gameplay::vehicle_kind::VehicleKind;
 use crate::menus::Menu;
 use crate::screens::Screen;
 use crate::theme::widget;
@@ -36,14 +37,16 @@ fn spawn_leaderboard(mut commands: Commands, leaderboard: Res<Leaderboard>) {
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    let mut result: Vec<[String; 3]> = Vec::new();
+    let mut result: Vec<[String; 4]> = Vec::new();
     for score in scores.iter().take(10) {
         let score = score.clone();
         let timestamp = score.timestamp.clone();
         let p = &timestamp[0..10];
         let player = score.player.clone();
         let score_str = score.score.to_string();
-        let data_array = [p.to_string(), player, score_str];
+        // the vehicle is sent along as meta, older scores have none
+        let vehicle = score.meta.clone().unwrap_or_default();
+        let data_array = [p.to_string(), player, score_str, vehicle];
         result.push(data_array);
     }
 
@@ -59,14 +62,14 @@ fn spawn_leaderboard(mut commands: Commands, leaderboard: Res<Leaderboard>) {
     ));
 }
 
-fn grid(content: Vec<[String; 3]>) -> impl Bundle {
+fn grid(content: Vec<[String; 4]>) -> impl Bundle {
     (
         Name::new("Grid"),
         Node {
             display: Display::Grid,
             row_gap: Px(10.0),
             column_gap: Px(30.0),
-            grid_template_columns: RepeatedGridTrack::px(3, 400.0),
+            grid_template_columns: RepeatedGridTrack::px(4, 300.0),
             ..default()
         },
         Children::spawn(SpawnIter(content.into_iter().flatten().enumerate().map(
@@ -74,7 +77,7 @@ fn grid(content: Vec<[String; 3]>) -> impl Bundle {
                 (
                     widget::label(text), // String implements Into<Text> so this should work
                     Node {
-                        justify_self: if i % 3 == 0 {
+                        justify_self: if i % 4 == 0 {
                             JustifySelf::End
                         } else {
                             JustifySelf::Start
@@ -102,6 +105,8 @@ fn go_back_on_click(
 #[derive(Event)]
 pub struct AddUserScore {
     pub value: f32,
+    /// what the player drove
+    pub vehicle: VehicleKind,
 }
 
 #[derive(Resource, Serialize, Deserialize, Debug, Default)]
@@ -123,12 +128,17 @@ impl User {
     }
 }
 
-fn setup_local_storage(mut commands: Commands) {
-    let config_dir = if let Some(config_dir) = dirs::config_dir() {
+/// Where the game keeps its local files
+pub(crate) fn config_dir() -> std::path::PathBuf {
+    if let Some(config_dir) = dirs::config_dir() {
         config_dir.join(GAME_NAME)
     } else {
         std::path::PathBuf::from("local").join(GAME_NAME)
-    };
+    }
+}
+
+fn setup_local_storage(mut commands: Commands) {
+    let config_dir = config_dir();
 
     commands.insert_resource(
         Persistent::<User>::builder()
@@ -149,6 +159,7 @@ fn test_create_score(
         event_writer.write(AddUserScore {
             // value: 1000.0,
             value: rand::random::<f32>() * 1000.0,
+            vehicle: VehicleKind::default(),
         });
     }
 }
@@ -209,7 +220,7 @@ fn add_score_to_leaderboard(
     println!("user score: {:#?}", user_score.value);
 
     // Send score to the leaderboard
-    leaderboard.send_score(user_score.value);
+    leaderboard.send_score_with_meta(user_score.value, user_score.vehicle.name());
 
     // Update user high score
     if user_score.value > user.high_score {
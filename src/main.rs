@@ -31,6 +31,10 @@ mod camera;
 mod leaderboard;
 
 fn main() -> AppExit {
+    if let Some(apples) = gameplay::apple_bench::apples_from_args() {
+        return gameplay::apple_bench::run(apples);
+    }
+
     App::new().add_plugins(AppPlugin).run()
 }
 
@@ -65,7 +69,10 @@ impl Plugin for AppPlugin {
         #[cfg(feature = "dev_native")]
         app.add_plugins(SimpleSubsecondPlugin::default());
 
-        app.add_plugins(PhysicsPlugins::default());
+        app.add_plugins(
+            PhysicsPlugins::default()
+                .with_collision_hooks::<gameplay::bullet::ProjectileCollisionHooks>(),
+        );
         app.add_plugins(bevy_ui_anchor::AnchorUiPlugin::<Camera>::new());
         app.add_plugins(bevy_rts_camera::RtsCameraPlugin);
 
@@ -4,13 +4,18 @@ use bevy::{
     ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
 };
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::LoadResource, audio::music, menus::Menu,
+    theme::navigation::gamepad_just_pressed, theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(
+            input_just_pressed(KeyCode::Escape).or(gamepad_just_pressed(GamepadButton::East)),
+        )),
     );
 
     app.register_type::<CreditsAssets>();
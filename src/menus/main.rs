@@ -13,7 +13,7 @@ use crate::{
         sun,
     },
     menus::Menu,
-    screens::Screen,
+    screens::{Screen, loading::LoadingTarget},
     theme::widget,
 };
 
@@ -47,7 +47,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         children![
             banner(&asset_server),
             widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Dev", enter_loading_or_dev_screen),
+            widget::button("Garage", enter_loading_or_garage_screen),
             widget::button("Leaderboard", open_leaderboard_screen),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
@@ -57,7 +57,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         children![
             banner(&asset_server),
             widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Dev", enter_loading_or_dev_screen),
+            widget::button("Garage", enter_loading_or_garage_screen),
             widget::button("Leaderboard", open_leaderboard_screen),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
@@ -72,6 +72,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         children![
             banner(&asset_server),
             widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Garage", enter_loading_or_garage_screen),
             widget::button("Leaderboard", open_leaderboard_screen),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
@@ -81,6 +82,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         children![
             banner(&asset_server),
             widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Garage", enter_loading_or_garage_screen),
             widget::button("Leaderboard", open_leaderboard_screen),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
@@ -115,23 +117,26 @@ fn enter_loading_or_gameplay_screen(
     _: Trigger<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
     mut next_screen: ResMut<NextState<Screen>>,
+    mut loading_target: ResMut<LoadingTarget>,
 ) {
     if resource_handles.is_all_done() {
         next_screen.set(Screen::InGame);
     } else {
+        loading_target.0 = Screen::InGame;
         next_screen.set(Screen::Loading);
     }
 }
 
-#[cfg(feature = "dev")]
-fn enter_loading_or_dev_screen(
+fn enter_loading_or_garage_screen(
     _: Trigger<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
     mut next_screen: ResMut<NextState<Screen>>,
+    mut loading_target: ResMut<LoadingTarget>,
 ) {
     if resource_handles.is_all_done() {
-        next_screen.set(Screen::TractorBuild);
+        next_screen.set(Screen::Garage);
     } else {
+        loading_target.0 = Screen::Garage;
         next_screen.set(Screen::Loading);
     }
 }
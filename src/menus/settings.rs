@@ -2,15 +2,32 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{
+    audio::Volume, ecs::system::IntoObserverSystem, input::common_conditions::input_just_pressed,
+    prelude::*, ui::Val::*,
+};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    gameplay::{
+        fuel::FuelEnabled,
+        lives::{MAX_LIVES, StartingLives},
+        turret_aiming::{
+            MAX_STICK_AIM_RADIUS, MAX_STICK_SENSITIVITY, MIN_STICK_AIM_RADIUS,
+            MIN_STICK_SENSITIVITY, StickAimSettings,
+        },
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{navigation::gamepad_just_pressed, prelude::*},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(
+            input_just_pressed(KeyCode::Escape).or(gamepad_just_pressed(GamepadButton::East)),
+        )),
     );
 
     app.register_type::<GlobalVolumeLabel>();
@@ -18,6 +35,24 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         update_global_volume_label.run_if(in_state(Menu::Settings)),
     );
+
+    app.register_type::<StartingLivesLabel>();
+    app.add_systems(
+        Update,
+        update_starting_lives_label.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<FuelEnabledLabel>();
+    app.add_systems(
+        Update,
+        update_fuel_enabled_label.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<StickAimLabel>();
+    app.add_systems(
+        Update,
+        update_stick_aim_labels.run_if(in_state(Menu::Settings)),
+    );
 }
 
 fn spawn_settings_menu(mut commands: Commands) {
@@ -52,6 +87,46 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("Lives"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            starting_lives_widget(),
+            (
+                widget::label("Fuel"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            fuel_enabled_widget(),
+            (
+                widget::label("Stick Aim Range"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            stick_aim_widget(
+                StickAimLabel::Radius,
+                lower_stick_radius,
+                raise_stick_radius
+            ),
+            (
+                widget::label("Stick Aim Speed"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            stick_aim_widget(
+                StickAimLabel::Sensitivity,
+                lower_stick_sensitivity,
+                raise_stick_sensitivity
+            ),
         ],
     )
 }
@@ -79,6 +154,79 @@ fn global_volume_widget() -> impl Bundle {
     )
 }
 
+fn starting_lives_widget() -> impl Bundle {
+    (
+        Name::new("Starting Lives Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_starting_lives),
+            (
+                Name::new("Current Lives"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), StartingLivesLabel)],
+            ),
+            widget::button_small("+", raise_starting_lives),
+        ],
+    )
+}
+
+fn fuel_enabled_widget() -> impl Bundle {
+    (
+        Name::new("Fuel Enabled Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", disable_fuel),
+            (
+                Name::new("Current Fuel"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), FuelEnabledLabel)],
+            ),
+            widget::button_small("+", enable_fuel),
+        ],
+    )
+}
+
+fn stick_aim_widget<ML, MR>(
+    label: StickAimLabel,
+    lower: impl IntoObserverSystem<Pointer<Click>, (), ML>,
+    raise: impl IntoObserverSystem<Pointer<Click>, (), MR>,
+) -> impl Bundle {
+    (
+        Name::new("Stick Aim Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower),
+            (
+                Name::new("Current Stick Aim"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), label)],
+            ),
+            widget::button_small("+", raise),
+        ],
+    )
+}
+
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
@@ -104,6 +252,82 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+fn lower_starting_lives(_: Trigger<Pointer<Click>>, mut lives: ResMut<StartingLives>) {
+    lives.0 = lives.0.saturating_sub(1).max(1);
+}
+
+fn raise_starting_lives(_: Trigger<Pointer<Click>>, mut lives: ResMut<StartingLives>) {
+    lives.0 = (lives.0 + 1).min(MAX_LIVES);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct StartingLivesLabel;
+
+fn update_starting_lives_label(
+    lives: Res<StartingLives>,
+    mut label: Single<&mut Text, With<StartingLivesLabel>>,
+) {
+    label.0 = format!("{}", lives.0);
+}
+
+fn disable_fuel(_: Trigger<Pointer<Click>>, mut fuel: ResMut<FuelEnabled>) {
+    fuel.0 = false;
+}
+
+fn enable_fuel(_: Trigger<Pointer<Click>>, mut fuel: ResMut<FuelEnabled>) {
+    fuel.0 = true;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FuelEnabledLabel;
+
+fn update_fuel_enabled_label(
+    fuel: Res<FuelEnabled>,
+    mut label: Single<&mut Text, With<FuelEnabledLabel>>,
+) {
+    label.0 = if fuel.0 { "On" } else { "Off" }.to_string();
+}
+
+const STICK_RADIUS_STEP: f32 = 5.0;
+const STICK_SENSITIVITY_STEP: f32 = 0.25;
+
+fn lower_stick_radius(_: Trigger<Pointer<Click>>, mut aim: ResMut<StickAimSettings>) {
+    aim.radius = (aim.radius - STICK_RADIUS_STEP).max(MIN_STICK_AIM_RADIUS);
+}
+
+fn raise_stick_radius(_: Trigger<Pointer<Click>>, mut aim: ResMut<StickAimSettings>) {
+    aim.radius = (aim.radius + STICK_RADIUS_STEP).min(MAX_STICK_AIM_RADIUS);
+}
+
+fn lower_stick_sensitivity(_: Trigger<Pointer<Click>>, mut aim: ResMut<StickAimSettings>) {
+    aim.sensitivity = (aim.sensitivity - STICK_SENSITIVITY_STEP).max(MIN_STICK_SENSITIVITY);
+}
+
+fn raise_stick_sensitivity(_: Trigger<Pointer<Click>>, mut aim: ResMut<StickAimSettings>) {
+    aim.sensitivity = (aim.sensitivity + STICK_SENSITIVITY_STEP).min(MAX_STICK_SENSITIVITY);
+}
+
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum StickAimLabel {
+    Radius,
+    Sensitivity,
+}
+
+fn update_stick_aim_labels(
+    aim: Res<StickAimSettings>,
+    mut labels: Query<(&mut Text, &StickAimLabel)>,
+) {
+    for (mut text, label) in labels.iter_mut() {
+        text.0 = match label {
+            StickAimLabel::Radius => format!("{:.0}", aim.radius),
+            StickAimLabel::Sensitivity => format!("{:.2}", aim.sensitivity),
+        };
+    }
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -0,0 +1,366 @@
+//! The garage where players put their tractor together before a run.
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::{prelude::*, ui::Val::*};
+use bevy_persistent::Persistent;
+
+use crate::gameplay::loadout::{Attachment, Chassis, Garage, Loadout, TurretMount, WheelSet};
+use crate::gameplay::sun;
+use crate::gameplay::tractor::{self, TractorAssets};
+use crate::gameplay::vehicle_kind::VehicleKind;
+use crate::theme::palette::BUTTON_BACKGROUND;
+use crate::{ReplaceOnHotreload, screens::Screen, theme::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SavedBrowser>();
+    app.init_resource::<PreviewBuild>();
+
+    app.add_systems(
+        OnEnter(Screen::Garage),
+        (spawn_garage, |mut preview: ResMut<PreviewBuild>| {
+            preview.0 = None
+        }),
+    );
+    app.add_systems(OnExit(Screen::Garage), (persist_garage, crate::cleanup));
+
+    app.add_systems(
+        Update,
+        (type_loadout_name, rebuild_preview, update_garage_texts)
+            .chain()
+            .run_if(in_state(Screen::Garage)),
+    );
+}
+
+/// Which saved loadout the garage is showing
+#[derive(Resource, Default)]
+struct SavedBrowser {
+    index: usize,
+}
+
+/// The build the preview was last spawned from, without its name
+#[derive(Resource, Default)]
+struct PreviewBuild(Option<Loadout>);
+
+/// What a garage button changes
+#[derive(Component, Clone, Copy, Debug)]
+enum GarageEdit {
+    Vehicle(i32),
+    Chassis(i32),
+    Wheels(i32),
+    Turret(TurretMount),
+    Attachment(Attachment),
+    Browse(i32),
+    Load,
+    Save,
+    Delete,
+}
+
+/// Text that shows a part of the garage state
+#[derive(Component, Clone, Copy, Debug)]
+enum GarageText {
+    Name,
+    Vehicle,
+    Chassis,
+    Wheels,
+    Turrets,
+    Attachments,
+    Stats,
+    Saved,
+}
+
+fn spawn_garage(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Garage"),
+            StateScoped(Screen::Garage),
+            GlobalZIndex(2),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Percent(3.),
+                top: Percent(5.),
+                padding: UiRect::all(Px(20.)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Px(12.),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            BorderRadius::all(Px(10.)),
+        ))
+        .with_children(|garage| {
+            garage.spawn(widget::header("Garage"));
+            garage.spawn(row(children![
+                widget::label("Name (type)"),
+                (widget::label(""), GarageText::Name),
+            ]));
+            garage.spawn(row(children![
+                widget::label("Vehicle"),
+                edit_button("<", GarageEdit::Vehicle(-1), 30.),
+                (widget::label(""), GarageText::Vehicle),
+                edit_button(">", GarageEdit::Vehicle(1), 30.),
+            ]));
+            garage.spawn(row(children![
+                widget::label("Chassis"),
+                edit_button("<", GarageEdit::Chassis(-1), 30.),
+                (widget::label(""), GarageText::Chassis),
+                edit_button(">", GarageEdit::Chassis(1), 30.),
+            ]));
+            garage.spawn(row(children![
+                widget::label("Wheels"),
+                edit_button("<", GarageEdit::Wheels(-1), 30.),
+                (widget::label(""), GarageText::Wheels),
+                edit_button(">", GarageEdit::Wheels(1), 30.),
+            ]));
+
+            garage.spawn((widget::label(""), GarageText::Turrets));
+            garage.spawn(row(()).with_children(|row| {
+                for mount in TurretMount::ALL {
+                    row.spawn(edit_button(mount.name(), GarageEdit::Turret(mount), 120.));
+                }
+            }));
+
+            garage.spawn((widget::label(""), GarageText::Attachments));
+            garage.spawn(row(()).with_children(|row| {
+                for attachment in Attachment::ALL {
+                    row.spawn(edit_button(
+                        attachment.name(),
+                        GarageEdit::Attachment(attachment),
+                        120.,
+                    ));
+                }
+            }));
+
+            garage.spawn((widget::label(""), GarageText::Stats));
+
+            garage.spawn(row(children![
+                widget::label("Saved"),
+                edit_button("<", GarageEdit::Browse(-1), 30.),
+                (widget::label(""), GarageText::Saved),
+                edit_button(">", GarageEdit::Browse(1), 30.),
+            ]));
+            garage.spawn(row(children![
+                edit_button("Load", GarageEdit::Load, 120.),
+                edit_button("Save", GarageEdit::Save, 120.),
+                edit_button("Delete", GarageEdit::Delete, 120.),
+            ]));
+
+            garage.spawn(widget::button("Play", enter_gameplay_screen));
+            garage.spawn(widget::button("Back", enter_title_screen));
+        });
+}
+
+fn row(children: impl Bundle) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Px(10.),
+            ..default()
+        },
+        children,
+    )
+}
+
+fn edit_button(text: &str, edit: GarageEdit, width: f32) -> impl Bundle {
+    widget::button_base(
+        text,
+        apply_edit,
+        (
+            Node {
+                width: Px(width),
+                height: Px(30.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BorderRadius::all(Px(5.)),
+            BackgroundColor(BUTTON_BACKGROUND),
+            edit,
+        ),
+    )
+}
+
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, step: i32) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0) as i32;
+    all[(index + step).rem_euclid(all.len() as i32) as usize]
+}
+
+fn apply_edit(
+    trigger: Trigger<Pointer<Click>>,
+    edits: Query<&GarageEdit>,
+    mut garage: ResMut<Persistent<Garage>>,
+    mut browser: ResMut<SavedBrowser>,
+) {
+    let Ok(edit) = edits.get(trigger.target()) else {
+        return;
+    };
+
+    let saved = garage.saved.len();
+    let index = browser.index;
+    // the build is only written to disk when it's saved or the garage is left
+    match *edit {
+        GarageEdit::Vehicle(step) => {
+            garage.current.vehicle = cycle(&VehicleKind::ALL, garage.current.vehicle, step);
+        }
+        GarageEdit::Chassis(step) => {
+            garage.current.chassis = cycle(&Chassis::ALL, garage.current.chassis, step);
+        }
+        GarageEdit::Wheels(step) => {
+            garage.current.wheels = cycle(&WheelSet::ALL, garage.current.wheels, step);
+        }
+        GarageEdit::Turret(mount) => garage.current.toggle_turret(mount),
+        GarageEdit::Attachment(attachment) => garage.current.toggle_attachment(attachment),
+        GarageEdit::Browse(step) => {
+            if saved > 0 {
+                browser.index = (index as i32 + step).rem_euclid(saved as i32) as usize;
+            }
+        }
+        GarageEdit::Load => {
+            if let Some(loadout) = garage.saved.get(index).cloned() {
+                garage.current = loadout;
+            }
+        }
+        GarageEdit::Save => {
+            garage.save_current();
+            save_garage(&garage);
+        }
+        GarageEdit::Delete => {
+            if index < garage.saved.len() {
+                garage.saved.remove(index);
+            }
+            save_garage(&garage);
+        }
+    }
+
+    // keep pointing at a saved loadout after saving or deleting
+    browser.index = browser.index.min(garage.saved.len().saturating_sub(1));
+}
+
+fn type_loadout_name(
+    mut keyboard: EventReader<KeyboardInput>,
+    mut garage: ResMut<Persistent<Garage>>,
+) {
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &input.logical_key {
+            Key::Character(text) => {
+                for character in text.chars().filter(|c| c.is_alphanumeric() || *c == ' ') {
+                    garage.current.push_name(character);
+                }
+            }
+            Key::Space => garage.current.push_name(' '),
+            Key::Backspace => {
+                garage.current.name.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn persist_garage(garage: Res<Persistent<Garage>>) {
+    save_garage(&garage);
+}
+
+/// A failed write keeps the garage for this session, it just isn't saved
+fn save_garage(garage: &Persistent<Garage>) {
+    if let Err(error) = garage.persist() {
+        error!("failed to save the garage: {error}");
+    }
+}
+
+fn update_garage_texts(
+    garage: Res<Persistent<Garage>>,
+    browser: Res<SavedBrowser>,
+    mut texts: Query<(&GarageText, &mut Text)>,
+) {
+    let current = &garage.current;
+    let stats = current.stats();
+
+    for (kind, mut text) in texts.iter_mut() {
+        text.0 = match kind {
+            GarageText::Name => format!("{}_", current.name),
+            GarageText::Vehicle => format!(
+                "{} ({})",
+                current.vehicle.name(),
+                current.vehicle.spec().ability.name()
+            ),
+            GarageText::Chassis => current.chassis.name().to_string(),
+            GarageText::Wheels => current.wheels.name().to_string(),
+            GarageText::Turrets => format!(
+                "Turrets: {}",
+                names(current.turrets.iter().map(|mount| mount.name()))
+            ),
+            GarageText::Attachments => format!(
+                "Attachments: {}",
+                names(
+                    current
+                        .attachments
+                        .iter()
+                        .map(|attachment| attachment.name())
+                )
+            ),
+            GarageText::Stats => format!(
+                "Mass {:.1}   Top speed {:.0}   Health {}",
+                stats.mass, stats.top_speed, stats.health
+            ),
+            GarageText::Saved => garage
+                .saved
+                .get(browser.index)
+                .map_or("none".to_string(), |loadout| loadout.name.clone()),
+        };
+    }
+}
+
+fn names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let names: Vec<&str> = names.collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Spawns the tractor of the current build to look at, whenever the build changes
+fn rebuild_preview(
+    mut commands: Commands,
+    garage: Res<Persistent<Garage>>,
+    mut preview: ResMut<PreviewBuild>,
+    assets: Res<TractorAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    previous: Query<Entity, With<ReplaceOnHotreload>>,
+) {
+    // renaming doesn't change what the tractor looks like
+    let build = Loadout {
+        name: String::new(),
+        ..garage.current.clone()
+    };
+    if preview.0.as_ref() == Some(&build) {
+        return;
+    }
+    preview.0 = Some(build);
+
+    for entity in previous.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    commands.spawn((sun(), StateScoped(Screen::Garage), ReplaceOnHotreload));
+    tractor::spawn_tractor(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &assets,
+        &garage.current,
+        (StateScoped(Screen::Garage), ReplaceOnHotreload),
+    );
+}
+
+fn enter_gameplay_screen(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::InGame);
+}
+
+fn enter_title_screen(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
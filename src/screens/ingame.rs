@@ -1,16 +1,19 @@
 //! The screen state for the main gameplay.
 
 use crate::gameplay::level::LevelAssets;
+use crate::gameplay::loadout::Garage;
 use crate::gameplay::{WorldAssets, tree::TreeSpawnEvent};
 use crate::{
     Pause,
     gameplay::tractor::{self, TractorAssets},
     menus::Menu,
     screens::Screen,
+    theme::navigation::gamepad_just_pressed,
 };
 use avian3d::math::PI;
 use bevy::pbr::CascadeShadowConfigBuilder;
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy_persistent::Persistent;
 
 pub(super) fn plugin(app: &mut App) {
     // Toggle pause on key press.
@@ -18,15 +21,15 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::InGame)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
-            ),
-            close_menu.run_if(
-                in_state(Screen::InGame)
-                    .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                in_state(Screen::InGame).and(in_state(Menu::None)).and(
+                    input_just_pressed(KeyCode::KeyP)
+                        .or(input_just_pressed(KeyCode::Escape))
+                        .or(gamepad_just_pressed(GamepadButton::Start)),
+                ),
             ),
+            close_menu.run_if(in_state(Screen::InGame).and(not(in_state(Menu::None))).and(
+                input_just_pressed(KeyCode::KeyP).or(gamepad_just_pressed(GamepadButton::Start)),
+            )),
         ),
     );
 
@@ -56,6 +59,7 @@ pub fn setup_gamescreen(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut currency: ResMut<Currency>,
+    garage: Res<Persistent<Garage>>,
     query: Query<Entity, With<ReplaceOnHotreload>>,
 ) {
     use bevy_enhanced_input::prelude::Actions;
@@ -75,6 +79,7 @@ pub fn setup_gamescreen(
         &mut meshes,
         &mut materials,
         &tractor_assets,
+        &garage.current,
         (
             ReplaceOnHotreload,
             StateScoped(Screen::InGame),
@@ -6,14 +6,26 @@ use bevy::prelude::*;
 use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LoadingTarget>();
+
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        enter_target_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
     );
 }
 
+/// The screen to continue to once everything is loaded
+#[derive(Resource, Debug)]
+pub struct LoadingTarget(pub Screen);
+
+impl Default for LoadingTarget {
+    fn default() -> Self {
+        Self(Screen::InGame)
+    }
+}
+
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
@@ -22,8 +34,8 @@ fn spawn_loading_screen(mut commands: Commands) {
     ));
 }
 
-fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::InGame);
+fn enter_target_screen(target: Res<LoadingTarget>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(target.0);
 }
 
 fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
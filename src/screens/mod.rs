@@ -2,29 +2,26 @@
 #[cfg(feature = "dev_native")]
 use bevy_simple_subsecond_system::hot;
 
+mod garage;
 pub(crate) mod ingame;
-mod loading;
+pub(crate) mod loading;
 mod music;
 mod splash;
 mod title;
 
-#[cfg(feature = "dev")]
-mod dev_tractorbuild;
-
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
 
     app.add_plugins((
+        garage::plugin,
         ingame::plugin,
         loading::plugin,
         splash::plugin,
         title::plugin,
         music::plugin,
     ));
-    #[cfg(feature = "dev")]
-    app.add_plugins(dev_tractorbuild::plugin);
 }
 
 /// The game's main screen states.
@@ -36,6 +33,5 @@ pub enum Screen {
     Title,
     Loading,
     InGame,
-    #[cfg(feature = "dev")]
-    TractorBuild,
+    Garage,
 }
@@ -0,0 +1,163 @@
+//! Moving between buttons with a gamepad's D-pad.
+
+use std::time::Duration;
+
+use bevy::{
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    window::{CursorMoved, PrimaryWindow},
+};
+
+use super::interaction::InteractionPalette;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<NavFocus>();
+    app.add_systems(
+        Update,
+        (
+            clear_focus_on_mouse,
+            move_focus,
+            press_focused,
+            highlight_focused,
+        )
+            .chain(),
+    );
+}
+
+/// The button the D-pad has moved to, the mouse takes it away again
+#[derive(Resource, Debug, Default)]
+pub struct NavFocus(pub Option<Entity>);
+
+/// Run condition for a button pressed on any gamepad, the gamepad version of `input_just_pressed`
+pub fn gamepad_just_pressed(button: GamepadButton) -> impl FnMut(Query<&Gamepad>) -> bool + Clone {
+    move |gamepads: Query<&Gamepad>| gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+}
+
+fn clear_focus_on_mouse(mut cursor: EventReader<CursorMoved>, mut focus: ResMut<NavFocus>) {
+    if cursor.read().count() > 0 && focus.0.is_some() {
+        focus.0 = None;
+    }
+}
+
+fn move_focus(
+    gamepads: Query<&Gamepad>,
+    buttons: Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    mut focus: ResMut<NavFocus>,
+) {
+    // ui space points down
+    let Some(direction) = gamepads.iter().find_map(|gamepad| {
+        [
+            (GamepadButton::DPadUp, Vec2::NEG_Y),
+            (GamepadButton::DPadDown, Vec2::Y),
+            (GamepadButton::DPadLeft, Vec2::NEG_X),
+            (GamepadButton::DPadRight, Vec2::X),
+        ]
+        .into_iter()
+        .find(|(button, _)| gamepad.just_pressed(*button))
+        .map(|(_, direction)| direction)
+    }) else {
+        return;
+    };
+
+    let visible = buttons
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation().xy()));
+
+    let current = focus
+        .0
+        .and_then(|entity| buttons.get(entity).ok())
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(_, transform, _)| transform.translation().xy());
+
+    let Some(from) = current else {
+        // start at the top of the menu
+        focus.0 = visible
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|(entity, _)| entity);
+        return;
+    };
+
+    // the closest button in that direction, straying sideways counts extra
+    let next = visible
+        .filter_map(|(entity, position)| {
+            let offset = position - from;
+            let along = offset.dot(direction);
+            (along > 1.0).then(|| (entity, along + offset.perp_dot(direction).abs() * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    if let Some((entity, _)) = next {
+        focus.0 = Some(entity);
+    }
+}
+
+/// Clicks the focused button, so its action runs like it would for the mouse
+fn press_focused(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    focus: Res<NavFocus>,
+    buttons: Query<&GlobalTransform, With<Button>>,
+    camera: Single<(Entity, &Camera)>,
+    window: Single<Entity, With<PrimaryWindow>>,
+) {
+    if !gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::South))
+    {
+        return;
+    }
+
+    let Some((button, transform)) = focus
+        .0
+        .and_then(|button| buttons.get(button).ok().map(|t| (button, t)))
+    else {
+        return;
+    };
+
+    let (camera_entity, camera) = *camera;
+    let Some(target) = camera.target.normalize(Some(*window)) else {
+        return;
+    };
+
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(camera_entity, 0.0, None, None),
+        duration: Duration::ZERO,
+    };
+    let location = Location {
+        target,
+        position: transform.translation().xy(),
+    };
+    commands.trigger_targets(
+        Pointer::new(PointerId::Mouse, location, button, click),
+        button,
+    );
+}
+
+fn highlight_focused(
+    focus: Res<NavFocus>,
+    mut buttons: Query<(
+        Entity,
+        &Interaction,
+        &InteractionPalette,
+        &mut BackgroundColor,
+    )>,
+) {
+    for (entity, interaction, palette, mut background) in buttons.iter_mut() {
+        // the mouse wins over the D-pad
+        if *interaction != Interaction::None {
+            continue;
+        }
+
+        let color = if focus.0 == Some(entity) {
+            palette.hovered
+        } else {
+            palette.none
+        };
+        background.set_if_neq(color.into());
+    }
+}